@@ -0,0 +1,85 @@
+//! Matchers 事件分发基准
+//!
+//! `cargo bench -p nonebot_rs --features matcher,scheduler --bench dispatch`
+
+use nonebot_rs::async_trait;
+use nonebot_rs::prelude::matchers::Matchers;
+use nonebot_rs::prelude::{Bot, BotConfig, Event, Handler, Matcher, MessageEvent};
+use std::time::Instant;
+use tokio::sync::{mpsc, watch};
+
+/// 仅匹配指定命令的 Handler
+struct Command(String);
+
+#[async_trait]
+impl Handler<MessageEvent> for Command {
+    fn match_(&self, event: &mut MessageEvent) -> bool {
+        event.get_raw_message().starts_with(&self.0)
+    }
+
+    async fn handle(&self, _: MessageEvent, _: &mut Matcher<MessageEvent>) {}
+}
+
+fn group_message(text: &str) -> Event {
+    let json = format!(
+        r#"{{
+            "post_type": "message",
+            "message_type": "group",
+            "time": 0,
+            "self_id": 1,
+            "sub_type": "normal",
+            "message_id": 1,
+            "group_id": 100,
+            "user_id": 2,
+            "anonymous": null,
+            "message": [{{"type": "text", "data": {{"text": "{text}"}}}}],
+            "raw_message": "{text}",
+            "font": 0,
+            "sender": {{
+                "user_id": 2, "nickname": "bench", "card": "", "sex": "unknown", "age": 0,
+                "area": "", "level": "", "role": "member", "title": ""
+            }}
+        }}"#,
+        text = text
+    );
+    serde_json::from_str(&json).expect("bench event")
+}
+
+fn build_matchers(count: usize) -> Matchers {
+    let mut matchers = Matchers::new_empty();
+    for i in 0..count {
+        let mut matcher = Matcher::new(&format!("command{}", i), Command(format!("cmd{} ", i)));
+        matchers.add_message_matcher(matcher.set_priority((i % 8) as i8).set_block(false));
+    }
+    matchers
+}
+
+fn main() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let (api_sender, _api_receiver) = mpsc::channel(1024);
+        let (action_sender, _action_receiver) = mpsc::channel(1024);
+        let (_resp_sender, resp_receiver) = watch::channel(serde_json::from_str(
+            r#"{"status": "ok", "retcode": 0, "data": null, "echo": ""}"#,
+        )
+        .unwrap());
+        let bot = Bot::new(1, BotConfig::default(), api_sender, action_sender, resp_receiver);
+
+        for count in [10, 100, 1000] {
+            let mut matchers = build_matchers(count);
+            let miss = group_message("nothing to match");
+            let iterations = 100_000 / count.max(1) * 10;
+            let start = Instant::now();
+            for _ in 0..iterations {
+                matchers.dispatch(miss.clone(), &bot).await;
+            }
+            let elapsed = start.elapsed();
+            println!(
+                "dispatch {:>5} matchers: {:>10.0} ns/event ({} events)",
+                count,
+                elapsed.as_nanos() as f64 / iterations as f64,
+                iterations
+            );
+        }
+    });
+}
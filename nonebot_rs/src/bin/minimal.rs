@@ -1,13 +1,18 @@
-use nonebot_rs;
-
 fn main() {
+    #[allow(unused_mut)]
     let mut nb = nonebot_rs::Nonebot::new();
     #[cfg(feature = "matcher")]
-    let mut matchers = nonebot_rs::Matchers::new_empty();
-    matchers
-        .add_message_matcher(nonebot_rs::builtin::echo::echo2())
-        .add_message_matcher(nonebot_rs::builtin::echo::echo())
-        .add_message_matcher(nonebot_rs::builtin::rcnb::rcnb());
-    nb.add_plugin(matchers);
+    {
+        use nonebot_rs::builtin;
+        use nonebot_rs::prelude::matchers::Matchers;
+
+        let mut matchers = Matchers::new_empty();
+        let help = builtin::help::help(&matchers);
+        let did_you_mean = builtin::did_you_mean::did_you_mean(&matchers);
+        matchers
+            .add_message_matcher(help)
+            .add_message_matcher(did_you_mean);
+        nb.add_plugin(matchers);
+    }
     nb.run()
 }
@@ -1,7 +1,8 @@
 
 use crate::api_resp;
-use crate::event::{Event, MessageEvent, NoticeEvent, NoticeSubType, NoticeType, RequestEvent, RequestType};
+use crate::event::{MessageEvent, MetaEvent, NoticeEvent, NoticeType, RequestEvent, RequestType};
 use crate::{api, config, utils, ApiChannelItem, ApiResp};
+use async_trait::async_trait;
 use colored::*;
 use tokio::sync::{mpsc, watch};
 use tracing::{event, Level};
@@ -261,3 +262,83 @@ impl Bot {
         None
     }
 }
+
+/// 可由 Bot 直接向来源回复消息的事件
+#[async_trait]
+pub trait Replyable {
+    /// 根据事件类型向来源私聊或群发送消息
+    async fn reply_by(&self, bot: &Bot, msg: crate::message::MessageChain);
+}
+
+/// 主动发送消息的目标
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendTarget {
+    /// 群聊
+    Group(i64),
+    /// 私聊
+    Private(i64),
+}
+
+impl SendTarget {
+    /// 通过指定 Bot 发送
+    pub async fn send_by(&self, bot: &Bot, msg: crate::message::MessageChain) -> crate::NBResult<()> {
+        let message_id = match *self {
+            SendTarget::Group(group_id) => bot.send_group_msg(group_id, msg, false).await,
+            SendTarget::Private(user_id) => bot.send_private_msg(user_id, msg, false).await,
+        };
+        match message_id {
+            Some(_) => Ok(()),
+            None => Err(crate::NBError::Api(format!("send to {:?} failed", self))),
+        }
+    }
+
+    /// 通过任一已连接的 Bot 发送，优先 bot_id 最小者
+    pub async fn send_any(
+        &self,
+        bots: &crate::BotGetter,
+        msg: crate::message::MessageChain,
+    ) -> crate::NBResult<()> {
+        let bot = bots
+            .borrow()
+            .values()
+            .min_by_key(|bot| bot.bot_id)
+            .cloned();
+        match bot {
+            Some(bot) => self.send_by(&bot, msg).await,
+            None => Err(crate::NBError::Api("no bot connected".to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl Replyable for MessageEvent {
+    async fn reply_by(&self, bot: &Bot, msg: crate::message::MessageChain) {
+        bot.send_by_message_event(self, msg).await;
+    }
+}
+
+#[async_trait]
+impl Replyable for NoticeEvent {
+    async fn reply_by(&self, bot: &Bot, msg: crate::message::MessageChain) {
+        bot.send_by_notice_event(self, msg).await;
+    }
+}
+
+#[async_trait]
+impl Replyable for RequestEvent {
+    async fn reply_by(&self, bot: &Bot, msg: crate::message::MessageChain) {
+        bot.send_by_request_event(self, msg).await;
+    }
+}
+
+#[async_trait]
+impl Replyable for MetaEvent {
+    async fn reply_by(&self, bot: &Bot, _: crate::message::MessageChain) {
+        event!(
+            Level::WARN,
+            "Bot [{}] {}",
+            bot.bot_id.to_string().red(),
+            "can not reply to MetaEvent".bright_red()
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::event::{MessageEvent, SelfId};
+use crate::matcher::matchers::Matchers;
+use crate::matcher::{
+    pre_matchers, AccessControl, AccessEntry, AccessKind, AccessTarget, ArgSpec, CommandHandler,
+    CommandSpec, Matcher, OnCommand, OptionSpec, ParsedCommand, Visibility,
+};
+use crate::utils::{parse_duration, timestamp};
+use crate::NBResult;
+use async_trait::async_trait;
+
+/// 黑白名单管理命令 Handler，仅 superuser 可用
+pub struct Access {
+    access: AccessControl,
+}
+
+fn render_entry(entry: &AccessEntry) -> String {
+    let mut line = format!("  [{}] {}", render_kind(entry.kind), entry.target);
+    if entry.bot_id.is_some() {
+        line.push_str(" (本 Bot)");
+    }
+    if let Some(expire) = entry.expire {
+        // 过期条目在清理前仍可能被列出
+        line.push_str(&format!(" 剩余 {} 秒", (expire - timestamp()).max(0)));
+    }
+    line
+}
+
+impl Access {
+    fn target(command: &ParsedCommand) -> NBResult<AccessTarget> {
+        let id = command.require::<i64>("id")?;
+        match command.require::<String>("target")?.as_str() {
+            "user" | "用户" => Ok(AccessTarget::User(id)),
+            "group" | "群" => Ok(AccessTarget::Group(id)),
+            other => Err(format!("未知名单对象 {}，可选 user 或 group", other).into()),
+        }
+    }
+}
+
+#[async_trait]
+impl CommandHandler for Access {
+    async fn handle(
+        &self,
+        command: ParsedCommand,
+        event: MessageEvent,
+        matcher: &mut Matcher<MessageEvent>,
+    ) -> NBResult<()> {
+        let config = matcher
+            .bot
+            .as_ref()
+            .map(|bot| bot.config.clone())
+            .unwrap_or_default();
+        if Visibility::of_sender(&event, &config) < Visibility::SuperUser {
+            return Err("仅 superuser 可以管理黑白名单".into());
+        }
+        let bot_id = event.get_self_id();
+        let kind = match command.sub_command() {
+            "list" => {
+                let entries = self.access.entries(bot_id);
+                let text = if entries.is_empty() {
+                    "黑白名单为空".to_string()
+                } else {
+                    let mut lines = vec!["黑白名单:".to_string()];
+                    lines.extend(entries.iter().map(render_entry));
+                    lines.join("\n")
+                };
+                matcher.send_text(&text).await;
+                return Ok(());
+            }
+            "block" | "unblock" => AccessKind::Block,
+            _ => AccessKind::Allow,
+        };
+        let target = Self::target(&command)?;
+        let scope_bot = if command.flag("bot") {
+            Some(bot_id)
+        } else {
+            None
+        };
+        let text = match command.sub_command() {
+            "block" | "allow" => {
+                let expire = match command.option::<String>("time")? {
+                    Some(time) => match parse_duration(&time).filter(|d| d.as_secs() > 0) {
+                        Some(duration) => Some(timestamp() + duration.as_secs() as i64),
+                        None => return Err(format!("无法识别的时长 {}", time).into()),
+                    },
+                    None => None,
+                };
+                self.access.add(kind, target, scope_bot, expire).await;
+                format!("已将{}加入{}", target, render_kind(kind))
+            }
+            _ => {
+                if !self.access.remove(kind, target, scope_bot).await {
+                    return Err(format!("{}不在{}中", target, render_kind(kind)).into());
+                }
+                format!("已将{}移出{}", target, render_kind(kind))
+            }
+        };
+        matcher.send_text(&text).await;
+        Ok(())
+    }
+}
+
+fn render_kind(kind: AccessKind) -> &'static str {
+    match kind {
+        AccessKind::Block => "黑名单",
+        AccessKind::Allow => "白名单",
+    }
+}
+
+fn entry_command(name: &str, with_time: bool) -> CommandSpec {
+    let spec = CommandSpec::new(name)
+        .add_arg(ArgSpec::required("target"))
+        .add_arg(ArgSpec::required("id"))
+        .add_option(OptionSpec::flag("bot", None));
+    if with_time {
+        spec.add_option(OptionSpec::value("time", Some('t')))
+    } else {
+        spec
+    }
+}
+
+/// 构建黑白名单管理 Matcher
+///
+/// - `access list` 列出对当前 Bot 生效的名单
+/// - `access block|allow <user|group> <id>` 加入黑名单或白名单，`-t 1h` 设置有效时长
+/// - `access unblock|disallow <user|group> <id>` 移出黑名单或白名单
+///
+/// 默认全局生效，`--bot` 仅对当前 Bot 生效
+pub fn access(matchers: &Matchers) -> Matcher<MessageEvent> {
+    let spec = CommandSpec::new("access")
+        .add_alias("名单")
+        .add_sub_command(CommandSpec::new("list"))
+        .add_sub_command(entry_command("block", true))
+        .add_sub_command(entry_command("unblock", false))
+        .add_sub_command(entry_command("allow", true))
+        .add_sub_command(entry_command("disallow", false));
+    OnCommand::matcher(
+        spec,
+        Access {
+            access: matchers.get_access(),
+        },
+    )
+    .add_pre_matcher(pre_matchers::command_start())
+    .set_description("管理用户与群黑白名单")
+    .set_category("内建")
+    .set_visibility(Visibility::SuperUser)
+}
@@ -0,0 +1,235 @@
+use crate::config::BotConfig;
+use crate::event::{GroupId, MessageEvent};
+use crate::matcher::matchers::{Matchers, MatchersInfo};
+use crate::matcher::{pre_matchers, Handler, Matcher, MatcherSwitches, Visibility};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 未知命令建议 Handler
+///
+/// 可在 Nonebotrs.toml 的 `[matcher.did_you_mean]` 中配置：
+///
+/// ```toml
+/// [matcher.did_you_mean]
+/// groups = []           # 仅在这些群启用，为空则所有群启用
+/// disable_groups = []   # 在这些群禁用
+/// private = true        # 是否在私聊启用
+/// max_distance = 2      # 最大编辑距离
+/// max_suggestions = 3   # 最多建议数
+/// ```
+#[derive(Clone)]
+pub struct DidYouMean {
+    infos: MatchersInfo,
+    switches: MatcherSwitches,
+    groups: Vec<i64>,
+    disable_groups: Vec<i64>,
+    private: bool,
+    max_distance: usize,
+    max_suggestions: usize,
+}
+
+impl DidYouMean {
+    pub fn new(infos: MatchersInfo, switches: MatcherSwitches) -> Self {
+        DidYouMean {
+            infos,
+            switches,
+            groups: vec![],
+            disable_groups: vec![],
+            private: true,
+            max_distance: 2,
+            max_suggestions: 3,
+        }
+    }
+
+    /// 该群（私聊为 0）是否启用
+    fn is_enabled(&self, group_id: i64) -> bool {
+        if group_id == 0 {
+            return self.private;
+        }
+        if self.disable_groups.contains(&group_id) {
+            return false;
+        }
+        self.groups.is_empty() || self.groups.contains(&group_id)
+    }
+
+    /// 当前用户可见且在该群启用的命令名与别名，已去除命令起始符
+    fn command_names(
+        &self,
+        level: Visibility,
+        group_id: i64,
+        command_starts: &[String],
+    ) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .infos
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .filter(|info| {
+                self.switches.is_enabled(&info.name, group_id, !info.disable)
+                    && level.can_see(info.meta.visibility)
+            })
+            .flat_map(|info| info.meta.commands.iter())
+            .map(|command| strip_command_start(command, command_starts).to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+#[async_trait]
+impl Handler<MessageEvent> for DidYouMean {
+    fn match_(&self, event: &mut MessageEvent) -> bool {
+        self.is_enabled(event.get_group_id())
+    }
+
+    async fn handle(&self, event: MessageEvent, matcher: &mut Matcher<MessageEvent>) {
+        let config = match &matcher.bot {
+            Some(bot) => bot.config.clone(),
+            None => return,
+        };
+        let level = Visibility::of_sender(&event, &config);
+        let names = self.command_names(level, event.get_group_id(), &config.command_starts);
+        let input = first_word(event.get_raw_message());
+        let suggestions = suggest(&names, input, self.max_distance, self.max_suggestions);
+        if suggestions.is_empty() {
+            return;
+        }
+        let start = config.command_starts.first().map(|s| s.as_str()).unwrap_or("");
+        let suggestions: Vec<String> = suggestions
+            .iter()
+            .map(|name| format!("{}{}", start, name))
+            .collect();
+        matcher
+            .send_text(&format!("未知命令 {}，你是不是想要: {}", input, suggestions.join(", ")))
+            .await;
+    }
+
+    fn load_config(&mut self, config: HashMap<String, toml::Value>) {
+        if let Some(groups) = config.get("groups").and_then(|v| v.clone().try_into().ok()) {
+            self.groups = groups;
+        }
+        if let Some(groups) = config
+            .get("disable_groups")
+            .and_then(|v| v.clone().try_into().ok())
+        {
+            self.disable_groups = groups;
+        }
+        if let Some(private) = config.get("private").and_then(|v| v.as_bool()) {
+            self.private = private;
+        }
+        if let Some(distance) = config.get("max_distance").and_then(|v| v.as_integer()) {
+            self.max_distance = distance.max(0) as usize;
+        }
+        if let Some(max) = config.get("max_suggestions").and_then(|v| v.as_integer()) {
+            self.max_suggestions = max.max(1) as usize;
+        }
+    }
+}
+
+fn strip_command_start<'a>(command: &'a str, command_starts: &[String]) -> &'a str {
+    for start in command_starts {
+        if !start.is_empty() && command.starts_with(start.as_str()) {
+            return &command[start.len()..];
+        }
+    }
+    command
+}
+
+fn first_word(raw_message: &str) -> &str {
+    raw_message.split_whitespace().next().unwrap_or("")
+}
+
+/// 计算两个字符串的编辑距离
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+/// 从 names 中找出与 input 最接近的命令
+///
+/// input 恰为已知命令时返回空，编辑距离不小于命令长度的候选会被忽略
+pub fn suggest(names: &[String], input: &str, max_distance: usize, max: usize) -> Vec<String> {
+    if input.is_empty() || names.iter().any(|name| name == input) {
+        return vec![];
+    }
+    let mut candidates: Vec<(usize, &String)> = names
+        .iter()
+        .map(|name| (edit_distance(input, name), name))
+        .filter(|(distance, name)| *distance <= max_distance && *distance < name.chars().count())
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .take(max)
+        .map(|(_, name)| name.clone())
+        .collect()
+}
+
+/// 判定消息首个词是否不是任何已知命令
+fn unknown_command(infos: MatchersInfo) -> crate::matcher::Rule<MessageEvent> {
+    let unknown = move |event: &MessageEvent, config: &BotConfig| -> bool {
+        let input = first_word(event.get_raw_message());
+        !config.command_starts.is_empty()
+            && !input.is_empty()
+            && !infos
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .values()
+                .flat_map(|info| info.meta.commands.iter())
+                .any(|command| strip_command_start(command, &config.command_starts) == input)
+    };
+    Arc::new(unknown)
+}
+
+/// 构建未知命令建议 Matcher
+///
+/// 以命令起始符开头但首个词不是任何已注册命令的消息，回复编辑距离最近的命令，
+/// 以最低优先级注册，不阻塞事件
+pub fn did_you_mean(matchers: &Matchers) -> Matcher<MessageEvent> {
+    let infos = matchers.get_infos();
+    let handler = DidYouMean::new(infos.clone(), matchers.get_switches());
+    Matcher::new("did_you_mean", handler)
+        .add_pre_matcher(pre_matchers::command_start())
+        .add_rule(unknown_command(infos))
+        .set_priority(i8::MAX)
+        .set_block(false)
+        .set_visibility(Visibility::Hidden)
+}
+
+#[test]
+fn did_you_mean_test() {
+    assert_eq!(edit_distance("", "help"), 4);
+    assert_eq!(edit_distance("hlep", "help"), 2);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("天气", "天汽"), 1);
+
+    let names: Vec<String> = ["help", "echo", "hello", "weather"]
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    // 距离相同时按命令名排序
+    assert_eq!(suggest(&names, "hepl", 2, 3), vec!["hello", "help"]);
+    assert_eq!(suggest(&names, "hepl", 2, 1), vec!["hello"]);
+    assert_eq!(suggest(&names, "hel", 1, 3), vec!["help"]);
+    assert_eq!(suggest(&names, "wether", 2, 3), vec!["weather"]);
+    // 已知命令、空输入与距离过大时不建议
+    assert!(suggest(&names, "help", 2, 3).is_empty());
+    assert!(suggest(&names, "", 2, 3).is_empty());
+    assert!(suggest(&names, "xyz", 2, 3).is_empty());
+
+    let starts = vec!["/".to_string()];
+    assert_eq!(strip_command_start("/help", &starts), "help");
+    assert_eq!(first_word("hepl me please"), "hepl");
+}
@@ -0,0 +1,120 @@
+use crate::event::{GroupId, MessageEvent};
+use crate::matcher::matchers::{Matchers, MatchersInfo};
+use crate::matcher::{
+    pre_matchers, ArgSpec, CommandHandler, CommandSpec, Matcher, MatcherInfo, MatcherSwitches,
+    OnCommand, ParsedCommand, Visibility,
+};
+use crate::NBResult;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+
+/// 未设置分类的 Matcher 归入的分类
+const DEFAULT_CATEGORY: &str = "其他";
+
+/// 帮助命令 Handler，读取 Matchers 中所有 Matcher 的元信息
+pub struct Help {
+    infos: MatchersInfo,
+    switches: MatcherSwitches,
+}
+
+#[async_trait]
+impl CommandHandler for Help {
+    async fn handle(
+        &self,
+        command: ParsedCommand,
+        event: MessageEvent,
+        matcher: &mut Matcher<MessageEvent>,
+    ) -> NBResult<()> {
+        let config = matcher
+            .bot
+            .as_ref()
+            .map(|bot| bot.config.clone())
+            .unwrap_or_default();
+        let level = Visibility::of_sender(&event, &config);
+        let group_id = event.get_group_id();
+        let infos: Vec<MatcherInfo> = self
+            .infos
+            .read()
+            .unwrap()
+            .values()
+            .filter(|info| {
+                self.switches.is_enabled(&info.name, group_id, !info.disable)
+                    && level.can_see(info.meta.visibility)
+            })
+            .cloned()
+            .collect();
+        let text = match command.arg::<String>("command")? {
+            Some(name) => match infos.iter().find(|info| info.is_name(&name)) {
+                Some(info) => render_detail(info),
+                None => return Err(format!("没有找到命令 {}", name).into()),
+            },
+            None => render_list(&infos),
+        };
+        matcher.send_text(&text).await;
+        Ok(())
+    }
+}
+
+/// 按分类渲染命令列表
+pub fn render_list(infos: &[MatcherInfo]) -> String {
+    let mut categories: BTreeMap<&str, Vec<&MatcherInfo>> = BTreeMap::new();
+    for info in infos {
+        let category = info.meta.category.as_deref().unwrap_or(DEFAULT_CATEGORY);
+        categories.entry(category).or_default().push(info);
+    }
+    let mut lines = vec!["可用命令:".to_string()];
+    for (category, mut infos) in categories {
+        infos.sort_by(|a, b| a.display_name().cmp(b.display_name()));
+        lines.push(format!("[{}]", category));
+        for info in infos {
+            match &info.meta.description {
+                Some(description) => {
+                    lines.push(format!("  {} - {}", info.display_name(), description))
+                }
+                None => lines.push(format!("  {}", info.display_name())),
+            }
+        }
+    }
+    lines.push("使用 help <命令> 查看详细用法".to_string());
+    lines.join("\n")
+}
+
+/// 渲染单个命令的帮助
+pub fn render_detail(info: &MatcherInfo) -> String {
+    let mut lines = vec![info.display_name().to_string()];
+    if info.meta.commands.len() > 1 {
+        lines.push(format!("别名: {}", info.meta.commands[1..].join(", ")));
+    }
+    if let Some(description) = &info.meta.description {
+        lines.push(description.clone());
+    }
+    if let Some(usage) = &info.meta.usage {
+        lines.push(format!("用法: {}", usage));
+    }
+    if !info.meta.examples.is_empty() {
+        lines.push("示例:".to_string());
+        for example in &info.meta.examples {
+            lines.push(format!("  {}", example));
+        }
+    }
+    lines.join("\n")
+}
+
+/// 构建帮助 Matcher
+///
+/// `help` 列出当前用户可见且在当前群启用的所有命令，`help <命令>` 显示该命令详细帮助
+pub fn help(matchers: &Matchers) -> Matcher<MessageEvent> {
+    let spec = CommandSpec::new("help")
+        .add_alias("帮助")
+        .add_arg(ArgSpec::optional("command"));
+    OnCommand::matcher(
+        spec,
+        Help {
+            infos: matchers.get_infos(),
+            switches: matchers.get_switches(),
+        },
+    )
+    .add_pre_matcher(pre_matchers::command_start())
+    .set_description("显示可用命令列表或指定命令的帮助")
+    .set_category("内建")
+}
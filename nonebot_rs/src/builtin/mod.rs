@@ -0,0 +1,8 @@
+/// 帮助命令
+pub mod help;
+/// 未知命令建议
+pub mod did_you_mean;
+/// Matcher 开关管理
+pub mod switch;
+/// 黑白名单管理
+pub mod access;
@@ -0,0 +1,150 @@
+use crate::event::{GroupId, MessageEvent};
+use crate::matcher::matchers::{Matchers, MatchersInfo};
+use crate::matcher::{
+    pre_matchers, ArgSpec, CommandHandler, CommandSpec, Matcher, MatcherInfo, MatcherSwitches,
+    OnCommand, OptionSpec, ParsedCommand, SwitchScope, Visibility,
+};
+use crate::NBResult;
+use async_trait::async_trait;
+
+/// 开关命令名，同时为其 Matcher 名称
+const COMMAND_NAME: &str = "matcher";
+
+/// Matcher 开关管理命令 Handler
+///
+/// superuser 可设置任意群与全局，群管理员与群主仅可设置本群
+pub struct Switch {
+    infos: MatchersInfo,
+    switches: MatcherSwitches,
+}
+
+impl Switch {
+    /// 解析作用范围，未指定时群聊为本群，私聊为全局
+    fn scope(command: &ParsedCommand, event: &MessageEvent) -> NBResult<SwitchScope> {
+        if command.flag("global") {
+            return Ok(SwitchScope::Global);
+        }
+        Ok(match command.option::<i64>("group")? {
+            Some(group_id) => SwitchScope::Group(group_id),
+            None if event.get_group_id() != 0 => SwitchScope::Group(event.get_group_id()),
+            None => SwitchScope::Global,
+        })
+    }
+
+    fn find(&self, name: &str) -> NBResult<MatcherInfo> {
+        match self
+            .infos
+            .read()
+            .unwrap()
+            .values()
+            .find(|info| info.is_name(name))
+        {
+            Some(info) => Ok(info.clone()),
+            None => Err(format!("没有找到 Matcher {}", name).into()),
+        }
+    }
+
+    fn render_list(&self, scope: SwitchScope) -> String {
+        let group_id = match scope {
+            SwitchScope::Global => 0,
+            SwitchScope::Group(group_id) => group_id,
+        };
+        let mut infos: Vec<MatcherInfo> = self.infos.read().unwrap().values().cloned().collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut lines = vec![format!("{} Matcher 状态:", scope)];
+        for info in infos {
+            let enabled = self.switches.is_enabled(&info.name, group_id, !info.disable);
+            lines.push(format!(
+                "  [{}] {}",
+                if enabled { "开" } else { "关" },
+                info.name
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+#[async_trait]
+impl CommandHandler for Switch {
+    async fn handle(
+        &self,
+        command: ParsedCommand,
+        event: MessageEvent,
+        matcher: &mut Matcher<MessageEvent>,
+    ) -> NBResult<()> {
+        let config = matcher
+            .bot
+            .as_ref()
+            .map(|bot| bot.config.clone())
+            .unwrap_or_default();
+        let level = Visibility::of_sender(&event, &config);
+        if level < Visibility::Admin {
+            return Err("仅群管理员与 superuser 可以管理 Matcher".into());
+        }
+        let scope = Self::scope(&command, &event)?;
+        if level < Visibility::SuperUser && scope != SwitchScope::Group(event.get_group_id()) {
+            return Err("群管理员仅能设置本群".into());
+        }
+        let enable = match command.sub_command() {
+            "list" => {
+                matcher.send_text(&self.render_list(scope)).await;
+                return Ok(());
+            }
+            "on" => Some(true),
+            "off" => Some(false),
+            _ => None,
+        };
+        let info = self.find(&command.require::<String>("name")?)?;
+        if info.name == COMMAND_NAME {
+            return Err("不能修改开关命令自身".into());
+        }
+        self.switches.set(&info.name, scope, enable).await;
+        let state = match enable {
+            Some(true) => "已启用",
+            Some(false) => "已禁用",
+            None => "已恢复默认",
+        };
+        matcher
+            .send_text(&format!("{} {} {}", scope, state, info.name))
+            .await;
+        Ok(())
+    }
+}
+
+fn scope_options(spec: CommandSpec) -> CommandSpec {
+    spec.add_option(OptionSpec::value("group", Some('g')))
+        .add_option(OptionSpec::flag("global", None))
+}
+
+/// 构建 Matcher 开关管理 Matcher
+///
+/// - `matcher list` 列出本群 Matcher 状态
+/// - `matcher on|off <name>` 在本群启用或禁用 Matcher
+/// - `matcher reset <name>` 清除本群设置，恢复全局设置
+///
+/// 均可使用 `-g <group_id>` 指定群或 `--global` 设置全局，设置保存于 `[matcher.switch] path`
+pub fn switch(matchers: &Matchers) -> Matcher<MessageEvent> {
+    let spec = CommandSpec::new(COMMAND_NAME)
+        .add_alias("开关")
+        .add_sub_command(scope_options(CommandSpec::new("list")))
+        .add_sub_command(scope_options(
+            CommandSpec::new("on").add_arg(ArgSpec::required("name")),
+        ))
+        .add_sub_command(scope_options(
+            CommandSpec::new("off").add_arg(ArgSpec::required("name")),
+        ))
+        .add_sub_command(scope_options(
+            CommandSpec::new("reset").add_arg(ArgSpec::required("name")),
+        ));
+    OnCommand::matcher(
+        spec,
+        Switch {
+            infos: matchers.get_infos(),
+            switches: matchers.get_switches(),
+        },
+    )
+    .add_pre_matcher(pre_matchers::command_start())
+    .set_description("启用或禁用 Matcher")
+    .set_category("内建")
+    .set_visibility(Visibility::Admin)
+}
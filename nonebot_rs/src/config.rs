@@ -6,6 +6,14 @@ use std::collections::HashMap;
 /// nbrs 配置文件名
 pub static CONFIG_PATH: &str = "Nonebotrs.toml";
 
+static LOADED_CONFIG: once_cell::sync::Lazy<std::sync::RwLock<Option<NbConfig>>> =
+    once_cell::sync::Lazy::new(|| std::sync::RwLock::new(None));
+
+/// 获取 Nonebot 启动时加载的配置
+pub fn loaded_config() -> Option<NbConfig> {
+    LOADED_CONFIG.read().unwrap().clone()
+}
+
 /// nbrs 配置项结构体
 #[derive(Serialize, Deserialize, Clone)]
 pub struct NbConfig {
@@ -15,6 +23,9 @@ pub struct NbConfig {
     pub bots: Option<HashMap<i64, BotConfig>>,
     /// 反向 WS 服务器设置
     pub ws_server: Option<WebSocketServerConfig>,
+    /// 存储设置
+    #[serde(default)]
+    pub store: Option<crate::store::StoreConfig>,
     #[serde(skip)]
     config: Config, // save the full config
 }
@@ -109,6 +120,7 @@ impl Default for NbConfig {
                 port: 8088,
                 access_token: String::default(),
             }),
+            store: None,
         }
     }
 }
@@ -133,6 +145,11 @@ impl NbConfig {
         config
     }
 
+    /// 设为全局已加载配置
+    pub(crate) fn set_loaded(&self) {
+        *LOADED_CONFIG.write().unwrap() = Some(self.clone());
+    }
+
     /// 根据 key_word 获取 config
     pub fn get_config<'de, T>(&self, key_word: &str) -> Option<T>
     where
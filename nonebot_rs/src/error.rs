@@ -3,13 +3,26 @@ pub enum NBError {
     Text(String),
     Other(crate::message::MessageChain),
     State(crate::matcher::Session),
+    /// Bot API 调用失败
+    Api(String),
+    /// 等待超时
+    Timeout,
+    /// 存储读写失败
+    Store(String),
 }
 
 impl std::error::Error for NBError {}
 
 impl std::fmt::Display for NBError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Error: {}", self)
+        match self {
+            NBError::Text(text) => write!(f, "{}", text),
+            NBError::Other(msg) => write!(f, "{:?}", msg),
+            NBError::State(session) => write!(f, "session state: {:?}", session),
+            NBError::Api(e) => write!(f, "api call failed: {}", e),
+            NBError::Timeout => write!(f, "timeout"),
+            NBError::Store(e) => write!(f, "store failed: {}", e),
+        }
     }
 }
 
@@ -598,7 +598,7 @@ impl GroupId for Event {
     fn get_group_id(&self) -> i64 {
         match self {
             Event::Message(m) => match m {
-                MessageEvent::Group(g) => g.user_id,
+                MessageEvent::Group(g) => g.group_id,
                 _ => 0,
             }
             Event::Notice(n) => {
@@ -680,6 +680,138 @@ impl SelfId for MetaEvent {
     }
 }
 
+impl SelfId for PrivateMessageEvent {
+    fn get_self_id(&self) -> i64 {
+        self.self_id
+    }
+}
+
+impl UserId for PrivateMessageEvent {
+    fn get_user_id(&self) -> i64 {
+        self.user_id
+    }
+}
+
+impl GroupId for PrivateMessageEvent {
+    fn get_group_id(&self) -> i64 {
+        0
+    }
+}
+
+impl SelfId for GroupMessageEvent {
+    fn get_self_id(&self) -> i64 {
+        self.self_id
+    }
+}
+
+impl UserId for GroupMessageEvent {
+    fn get_user_id(&self) -> i64 {
+        self.user_id
+    }
+}
+
+impl GroupId for GroupMessageEvent {
+    fn get_group_id(&self) -> i64 {
+        self.group_id
+    }
+}
+
+impl UserId for MetaEvent {
+    fn get_user_id(&self) -> i64 {
+        0
+    }
+}
+
+impl GroupId for MetaEvent {
+    fn get_group_id(&self) -> i64 {
+        0
+    }
+}
+
+/// 事件类型与子类型名称，与 OneBot 上报字段一致
+///
+/// 用于 `#[event]` 中 notice_type、request_type、meta_event_type 与 sub_type 的过滤
+pub trait EventTypeName {
+    /// notice_type|request_type|meta_event_type
+    fn type_name(&self) -> String;
+    /// sub_type，无子类型时为 None
+    fn sub_type_name(&self) -> Option<String>;
+}
+
+/// 取 serde 序列化后的枚举名称
+fn serde_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => String::default(),
+    }
+}
+
+impl EventTypeName for NoticeEvent {
+    fn type_name(&self) -> String {
+        serde_name(&self.notice_type)
+    }
+
+    fn sub_type_name(&self) -> Option<String> {
+        self.sub_type.as_ref().map(serde_name)
+    }
+}
+
+impl EventTypeName for RequestEvent {
+    fn type_name(&self) -> String {
+        serde_name(&self.request_type)
+    }
+
+    fn sub_type_name(&self) -> Option<String> {
+        self.sub_type.as_ref().map(serde_name)
+    }
+}
+
+impl EventTypeName for MetaEvent {
+    fn type_name(&self) -> String {
+        self.meta_event_type.clone()
+    }
+
+    fn sub_type_name(&self) -> Option<String> {
+        self.sub_type.clone()
+    }
+}
+
+impl From<MessageEvent> for Event {
+    fn from(e: MessageEvent) -> Self {
+        Event::Message(e)
+    }
+}
+
+impl From<PrivateMessageEvent> for Event {
+    fn from(e: PrivateMessageEvent) -> Self {
+        Event::Message(MessageEvent::Private(e))
+    }
+}
+
+impl From<GroupMessageEvent> for Event {
+    fn from(e: GroupMessageEvent) -> Self {
+        Event::Message(MessageEvent::Group(e))
+    }
+}
+
+impl From<NoticeEvent> for Event {
+    fn from(e: NoticeEvent) -> Self {
+        Event::Notice(e)
+    }
+}
+
+impl From<RequestEvent> for Event {
+    fn from(e: RequestEvent) -> Self {
+        Event::Request(e)
+    }
+}
+
+impl From<MetaEvent> for Event {
+    fn from(e: MetaEvent) -> Self {
+        Event::Meta(e)
+    }
+}
+
 impl SelfId for Event {
     fn get_self_id(&self) -> i64 {
         match self {
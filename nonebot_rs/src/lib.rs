@@ -39,6 +39,10 @@
 //! command_starts = ["/"]       # 命令起始符
 //! ws_server = "server address" # 正向 WS 服务器地址（缺省不启用正向 WS 连接）
 //! access_token = "AccessToken" # 连接鉴权使用
+//!
+//! [store]                      # Plugin 键值存储（缺省为 memory，打开失败时同样回退为 memory）
+//! backend = "sled"             # sled（需启用 feature sled，设置 [store] 时的缺省值）或 memory
+//! path = "data/store"          # sled 数据目录
 //! ```
 //!
 //! ## Plugin
@@ -121,26 +125,17 @@
 //! 定义一个定时任务
 //!
 //! ```rust
-//! use nonebot_rs::{Job, Message};
-//!
-//! pub fn clock(nb: &nonebot_rs::Nonebot) -> Job {
-//!     let bot_getter = nb.bot_getter.clone();
-//!     Job::new("1 * * * * *", move |_, _| {
-//!         let bots = bot_getter.borrow().clone();
-//!         for (_, bot) in bots {
-//!             let bot = bot.clone();
-//!             tokio::spawn(send_a_msg(bot));
-//!         }
-//!     })
-//!     .unwrap()
-//! }
+//! use nonebot_rs::prelude::*;
+//! use std::sync::Arc;
 //!
-//! // Just for test
-//! async fn send_a_msg(bot: nonebot_rs::Bot) {
+//! // 每天 8 点（UTC+8）由每个 Bot 各执行一次，也可使用 every、at、delay 与 jitter、max_runs、bot
+//! #[scheduler(cron = "0 0 8 * * *", tz = "+08:00")]
+//! async fn clock(bot: Arc<Bot>) {
 //!     for superuser in &bot.config.superusers {
 //!         bot.send_private_msg(
 //!             superuser.parse().unwrap(),
-//!             vec![Message::text("One minute passed.")],
+//!             vec![Message::text("早上好")],
+//!             false,
 //!         )
 //!         .await;
 //!     }
@@ -149,20 +144,38 @@
 //!
 //! 注册定时任务
 //!
-//! ```rust
-//! use nonebot_rs;
+//! `#[plugin]` mod 中的 `#[scheduler]` 会在启动时自动注册，无需手动添加 Scheduler；也可手动注册：
 //!
-//! fn main() {
+//! ```rust,ignore
+//! use nonebot_rs::prelude::Scheduler;
+//!
+//! #[tokio::main]
+//! async fn main() {
 //!     let mut nb = nonebot_rs::Nonebot::new();
 //!
-//!     let mut scheduler = nonebot_rs::Scheduler::new();
-//!     scheduler.add_job(clock::clock(&nb));
+//!     let mut scheduler = Scheduler::new().await;
+//!     scheduler.add_task(clock).await;
 //!     nb.add_plugin(scheduler);
 //!
-//!     nb.run()
+//!     nb.async_run().await
 //! }
 //! ```
 //!
+//! 启动时检查所有任务的调度设置，存在无效设置时启动失败；之后按任务的 `bot` 设置（`JobTarget`）调度：
+//!
+//! - 缺省 `bot = "each"`（`JobTarget::EachBot`）：每个 Bot 连接时为其调度一份，Bot 断开时移除
+//! - `bot = "any"`（`JobTarget::AnyBot`）：启动时调度，每次执行时任选一个已连接的 Bot，无 Bot 时跳过
+//! - `bot = 123`（`JobTarget::Bot(123)`）：启动时调度，由指定 Bot 执行，未连接时跳过
+//!
+//! 运行时添加一次性任务
+//!
+//! ```rust,ignore
+//! let job = Job::new("remind", Schedule::after(parse_duration("10m").unwrap()), |bot| async move {
+//!     bot.send_private_msg(user_id, vec![Message::text("时间到了")]).await;
+//! });
+//! add_job(bot, job).await?;
+//! ```
+//!
 //! enjoy~
 
 /////////////////////////////////////////////////////////////////////////////////
@@ -173,6 +186,10 @@ mod api;
 /// Onebot Api Response
 mod api_resp;
 mod bot;
+/// 内建 Matcher
+#[cfg(feature = "matcher")]
+#[cfg_attr(docsrs, doc(cfg(feature = "matcher")))]
+pub mod builtin;
 
 #[doc(hidden)]
 mod comms;
@@ -191,8 +208,12 @@ mod nb;
 #[doc(hidden)]
 mod plugin;
 /// scheduler Plugin
-
+#[cfg(feature = "scheduler")]
 mod scheduler;
+/// 按作用域存储的 handler 状态
+mod state;
+/// Plugin 键值存储
+mod store;
 mod utils;
 mod cq_code;
 mod error;
@@ -201,6 +222,10 @@ pub use error::{
     NBResult, NBError,
 };
 pub use async_trait::async_trait;
+#[doc(hidden)]
+pub use regex;
+#[doc(hidden)]
+pub use inventory;
 
 pub mod prelude {
     pub use crate::cq_code::*;
@@ -209,7 +234,7 @@ pub mod prelude {
     pub use crate::scheduler::*;
     
     pub use nonebot_rs_macros::{
-        event, scheduler,
+        event, plugin, scheduler, send, FromCommand,
     };
     
     #[cfg(feature = "matcher")]
@@ -227,11 +252,15 @@ pub mod prelude {
         message::*,
         nb::*,
         config::*,
-        bot::Bot,
+        state::*,
+        store::*,
+        api_resp::{GroupInfo, GroupMemberInfo},
+        bot::{Bot, Replyable, SendTarget},
+        plugin::{check_plugin_config, registered_plugins, PluginDef},
         matcher_build,
         config::BotConfig,
         utils::{
-            remove_space, timestamp,
+            parse_duration, remove_space, timestamp,
         },
     };
     use crate::log;
@@ -317,9 +346,3 @@ pub enum ApiChannelItem {
     /// Time out 通知T
     TimeOut,
 }
-
-pub type State = anymap::Map<dyn core::any::Any + Send>;
-
-pub static mut STATE: once_cell::sync::Lazy<tokio::sync::Mutex<State>> = once_cell::sync::Lazy::new(|| tokio::sync::Mutex::new(State::new()));
-
-
@@ -0,0 +1,441 @@
+use crate::config::BotConfig;
+use crate::event::{Event, GroupId, SelfId, UserId};
+use crate::utils::timestamp;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use tracing::{event, Level};
+
+/// 默认持久化文件
+pub const DEFAULT_ACCESS_PATH: &str = "matcher_access.json";
+
+/// 名单类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    /// 黑名单，名单内的用户或群被忽略
+    Block,
+    /// 白名单，非空时仅响应名单内的用户或群
+    Allow,
+}
+
+/// 名单对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessTarget {
+    User(i64),
+    Group(i64),
+}
+
+impl std::fmt::Display for AccessTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccessTarget::User(user_id) => write!(f, "用户 {}", user_id),
+            AccessTarget::Group(group_id) => write!(f, "群 {}", group_id),
+        }
+    }
+}
+
+/// 名单条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessEntry {
+    pub kind: AccessKind,
+    pub target: AccessTarget,
+    /// 仅对该 Bot 生效，None 为全局
+    pub bot_id: Option<i64>,
+    /// 过期时间戳，None 为永久
+    pub expire: Option<i64>,
+}
+
+/// id -> 过期时间戳
+type Expires = HashMap<i64, Option<i64>>;
+
+fn alive(expires: &Expires, id: i64, now: i64) -> bool {
+    match expires.get(&id) {
+        Some(Some(expire)) => *expire > now,
+        Some(None) => true,
+        None => false,
+    }
+}
+
+fn any_alive(expires: &Expires, now: i64) -> bool {
+    expires
+        .values()
+        .any(|expire| expire.iter().all(|expire| *expire > now))
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AccessList {
+    #[serde(default)]
+    users: Expires,
+    #[serde(default)]
+    groups: Expires,
+}
+
+impl AccessList {
+    fn expires(&mut self, target: AccessTarget) -> (&mut Expires, i64) {
+        match target {
+            AccessTarget::User(user_id) => (&mut self.users, user_id),
+            AccessTarget::Group(group_id) => (&mut self.groups, group_id),
+        }
+    }
+
+    fn purge(&mut self, now: i64) {
+        let alive = |_: &i64, expire: &mut Option<i64>| expire.iter().all(|e| *e > now);
+        self.users.retain(alive);
+        self.groups.retain(alive);
+    }
+
+    fn load_config(&mut self, users: Option<&toml::Value>, groups: Option<&toml::Value>) {
+        let ids = |value: Option<&toml::Value>| -> Vec<i64> {
+            value
+                .and_then(|v| v.as_array())
+                .map(|ids| ids.iter().filter_map(|id| id.as_integer()).collect())
+                .unwrap_or_default()
+        };
+        self.users = ids(users).into_iter().map(|id| (id, None)).collect();
+        self.groups = ids(groups).into_iter().map(|id| (id, None)).collect();
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AccessLists {
+    #[serde(default)]
+    block: AccessList,
+    #[serde(default)]
+    allow: AccessList,
+}
+
+impl AccessLists {
+    fn list(&mut self, kind: AccessKind) -> &mut AccessList {
+        match kind {
+            AccessKind::Block => &mut self.block,
+            AccessKind::Allow => &mut self.allow,
+        }
+    }
+
+    fn load_config(&mut self, config: &toml::value::Table) {
+        self.block
+            .load_config(config.get("block_users"), config.get("block_groups"));
+        self.allow
+            .load_config(config.get("allow_users"), config.get("allow_groups"));
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AccessState {
+    #[serde(default)]
+    global: AccessLists,
+    #[serde(default)]
+    bots: HashMap<i64, AccessLists>,
+}
+
+impl AccessState {
+    fn lists(&mut self, bot_id: Option<i64>) -> &mut AccessLists {
+        match bot_id {
+            Some(bot_id) => self.bots.entry(bot_id).or_default(),
+            None => &mut self.global,
+        }
+    }
+
+    /// 对该 Bot 生效的所有名单
+    fn layers(&self, bot_id: i64) -> impl Iterator<Item = &AccessLists> {
+        std::iter::once(&self.global).chain(self.bots.get(&bot_id))
+    }
+}
+
+/// 用户与群黑白名单，在所有 Matcher 运行前检查，superuser 不受限制
+///
+/// 分为全局与指定 Bot 两级，配置文件中的名单永久生效，
+/// 通过命令添加的名单可设置过期时间并写入 JSON 文件，重启后自动恢复
+///
+/// ```toml
+/// [matcher.access]
+/// path = "matcher_access.json"
+/// block_users = [10001]
+/// block_groups = []
+/// allow_users = []       # 非空时仅响应名单内用户
+/// allow_groups = []      # 非空时仅响应名单内群的群事件
+///
+/// [matcher.access.bots.BotID]
+/// allow_groups = [12345]
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AccessControl {
+    /// 命令添加的名单，持久化
+    state: Arc<RwLock<AccessState>>,
+    /// 配置文件中的名单
+    config: Arc<RwLock<AccessState>>,
+    path: Arc<RwLock<Option<PathBuf>>>,
+    /// 依次写入，保证文件内容为最后一次修改后的状态
+    saving: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl AccessControl {
+    /// 设置持久化文件路径
+    pub fn set_path<P: Into<PathBuf>>(&self, path: P) {
+        *self.path.write().unwrap() = Some(path.into());
+    }
+
+    /// 从持久化文件恢复，未设置路径时使用默认路径
+    pub fn load(&self) {
+        let path = self
+            .path
+            .write()
+            .unwrap()
+            .get_or_insert_with(|| PathBuf::from(DEFAULT_ACCESS_PATH))
+            .clone();
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(_) => return,
+        };
+        match serde_json::from_str(&data) {
+            Ok(state) => *self.state.write().unwrap() = state,
+            Err(e) => event!(
+                Level::WARN,
+                "Failed to load access lists from {:?}: {}",
+                path,
+                e
+            ),
+        }
+    }
+
+    async fn save(&self) {
+        let path = match self.path.read().unwrap().clone() {
+            Some(path) => path,
+            None => return,
+        };
+        let _saving = self.saving.lock().await;
+        let data = {
+            let mut guard = self.state.write().unwrap();
+            let state = &mut *guard;
+            let now = timestamp();
+            for lists in std::iter::once(&mut state.global).chain(state.bots.values_mut()) {
+                lists.block.purge(now);
+                lists.allow.purge(now);
+            }
+            serde_json::to_string_pretty(&*state).unwrap()
+        };
+        if let Err(e) = tokio::fs::write(&path, data).await {
+            event!(
+                Level::WARN,
+                "Failed to save access lists to {:?}: {}",
+                path,
+                e
+            );
+        }
+    }
+
+    pub(crate) fn load_config(&self, config: &HashMap<String, toml::Value>) {
+        if let Some(path) = config.get("path").and_then(|v| v.as_str()) {
+            self.set_path(path);
+        }
+        let mut state = AccessState::default();
+        let global: toml::value::Table =
+            config.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        state.global.load_config(&global);
+        if let Some(bots) = config.get("bots").and_then(|v| v.as_table()) {
+            for (bot_id, bot_config) in bots {
+                match (bot_id.parse::<i64>(), bot_config.as_table()) {
+                    (Ok(bot_id), Some(bot_config)) => {
+                        state.lists(Some(bot_id)).load_config(bot_config)
+                    }
+                    _ => event!(Level::WARN, "Invalid access config for bot {}", bot_id),
+                }
+            }
+        }
+        *self.config.write().unwrap() = state;
+    }
+
+    /// 添加名单条目，bot_id 为 None 时全局生效，expire 为过期时间戳，返回时已写入持久化文件
+    pub async fn add(
+        &self,
+        kind: AccessKind,
+        target: AccessTarget,
+        bot_id: Option<i64>,
+        expire: Option<i64>,
+    ) {
+        {
+            let mut state = self.state.write().unwrap();
+            let (expires, id) = state.lists(bot_id).list(kind).expires(target);
+            expires.insert(id, expire);
+        }
+        self.save().await;
+    }
+
+    /// 移除名单条目，返回条目是否存在（不含配置文件中的名单）
+    pub async fn remove(
+        &self,
+        kind: AccessKind,
+        target: AccessTarget,
+        bot_id: Option<i64>,
+    ) -> bool {
+        let removed = {
+            let mut state = self.state.write().unwrap();
+            let (expires, id) = state.lists(bot_id).list(kind).expires(target);
+            expires.remove(&id).is_some()
+        };
+        if removed {
+            self.save().await;
+        }
+        removed
+    }
+
+    /// 列出对该 Bot 生效的未过期名单条目
+    pub fn entries(&self, bot_id: i64) -> Vec<AccessEntry> {
+        let now = timestamp();
+        let mut entries = vec![];
+        for state in [&self.config, &self.state] {
+            let state = state.read().unwrap();
+            let layers = std::iter::once((None, &state.global))
+                .chain(state.bots.get(&bot_id).map(|lists| (Some(bot_id), lists)));
+            for (lists_bot_id, lists) in layers {
+                for (kind, list) in [
+                    (AccessKind::Block, &lists.block),
+                    (AccessKind::Allow, &lists.allow),
+                ] {
+                    let users = list
+                        .users
+                        .iter()
+                        .map(|(id, e)| (AccessTarget::User(*id), *e));
+                    let groups = list
+                        .groups
+                        .iter()
+                        .map(|(id, e)| (AccessTarget::Group(*id), *e));
+                    for (target, expire) in users.chain(groups) {
+                        if expire.iter().all(|expire| *expire > now) {
+                            entries.push(AccessEntry {
+                                kind,
+                                target,
+                                bot_id: lists_bot_id,
+                                expire,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        entries
+    }
+
+    /// 检查 Bot 是否响应该用户与群（非群事件 group_id 为 0）
+    pub fn is_allowed(&self, bot_id: i64, user_id: i64, group_id: i64, config: &BotConfig) -> bool {
+        let user = user_id.to_string();
+        if config.superusers.iter().any(|s| s == &user) {
+            return true;
+        }
+        let now = timestamp();
+        let state = self.state.read().unwrap();
+        let config = self.config.read().unwrap();
+        let layers: Vec<&AccessLists> = config.layers(bot_id).chain(state.layers(bot_id)).collect();
+
+        let blocked = layers.iter().any(|lists| {
+            alive(&lists.block.users, user_id, now)
+                || (group_id != 0 && alive(&lists.block.groups, group_id, now))
+        });
+        if blocked {
+            return false;
+        }
+        if user_id != 0
+            && layers
+                .iter()
+                .any(|lists| any_alive(&lists.allow.users, now))
+            && !layers
+                .iter()
+                .any(|lists| alive(&lists.allow.users, user_id, now))
+        {
+            return false;
+        }
+        if group_id != 0
+            && layers
+                .iter()
+                .any(|lists| any_alive(&lists.allow.groups, now))
+            && !layers
+                .iter()
+                .any(|lists| alive(&lists.allow.groups, group_id, now))
+        {
+            return false;
+        }
+        true
+    }
+
+    /// 检查事件是否应被分发，Meta 与 Nonebot 事件不受限制
+    pub fn is_event_allowed(&self, event: &Event, config: &BotConfig) -> bool {
+        match event {
+            Event::Message(_) | Event::Notice(_) | Event::Request(_) => self.is_allowed(
+                event.get_self_id(),
+                event.get_user_id(),
+                event.get_group_id(),
+                config,
+            ),
+            Event::Meta(_) | Event::Nonebot(_) => true,
+        }
+    }
+}
+
+#[tokio::test]
+async fn access_test() {
+    let path = std::env::temp_dir().join(format!("nbrs_access_{}.json", std::process::id()));
+    let access = AccessControl::default();
+    access.set_path(&path);
+    let config: HashMap<String, toml::Value> =
+        toml::from_str("block_users = [2]\n[bots.100]\nallow_groups = [10]").unwrap();
+    access.load_config(&config);
+    let mut bot_config = BotConfig::default();
+    bot_config.superusers = vec!["2".to_string()];
+
+    // 黑名单优先于白名单，superuser 不受限制
+    assert!(!access.is_allowed(100, 2, 10, &BotConfig::default()));
+    assert!(access.is_allowed(100, 2, 20, &bot_config));
+    // Bot 级白名单只对该 Bot 生效，私聊不受群白名单限制
+    assert!(access.is_allowed(100, 1, 10, &BotConfig::default()));
+    assert!(!access.is_allowed(100, 1, 20, &BotConfig::default()));
+    assert!(access.is_allowed(100, 1, 0, &BotConfig::default()));
+    assert!(access.is_allowed(200, 1, 20, &BotConfig::default()));
+
+    // 全局白名单与 Bot 级白名单叠加
+    access
+        .add(AccessKind::Allow, AccessTarget::Group(20), None, None)
+        .await;
+    assert!(access.is_allowed(100, 1, 20, &BotConfig::default()));
+    assert!(!access.is_allowed(200, 1, 30, &BotConfig::default()));
+
+    // 过期条目不生效，也不会让白名单继续限制其他用户
+    access
+        .add(
+            AccessKind::Allow,
+            AccessTarget::User(3),
+            None,
+            Some(timestamp() - 1),
+        )
+        .await;
+    assert!(access.is_allowed(200, 1, 0, &BotConfig::default()));
+    access
+        .add(
+            AccessKind::Block,
+            AccessTarget::User(4),
+            Some(200),
+            Some(timestamp() + 60),
+        )
+        .await;
+    assert!(!access.is_allowed(200, 4, 0, &BotConfig::default()));
+    assert!(access.is_allowed(100, 4, 10, &BotConfig::default()));
+
+    // 持久化仅包含命令添加且未过期的条目
+    let restored = AccessControl::default();
+    restored.set_path(&path);
+    restored.load();
+    let entries = restored.entries(200);
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|e| e.target != AccessTarget::User(3)));
+    assert!(
+        restored
+            .remove(AccessKind::Block, AccessTarget::User(4), Some(200))
+            .await
+    );
+    assert!(
+        !restored
+            .remove(AccessKind::Block, AccessTarget::User(4), Some(200))
+            .await
+    );
+    let _ = std::fs::remove_file(&path);
+}
@@ -1,4 +1,5 @@
 use crate::log::{colored::*, event, Level};
+use tokio::sync::{mpsc, oneshot};
 
 /// Matchers 内部 Action
 #[derive(Clone, Debug)]
@@ -15,11 +16,49 @@ pub enum MatchersAction {
     },
     /// 移除 Matcher
     RemoveMatcher { matcher_name: String },
+    /// 启用或禁用 Matcher
+    DisableMatcher { matcher_name: String, disable: bool },
+}
+
+type ActionItem = (MatchersAction, Option<oneshot::Sender<bool>>);
+
+/// Matchers Action 发送端
+///
+/// Action 按发送顺序处理，Matchers 在分发下一个事件前处理完所有已发送的 Action
+#[derive(Clone, Debug)]
+pub struct MatchersActionSender {
+    sender: mpsc::UnboundedSender<ActionItem>,
+}
+
+/// Matchers Action 接收端
+pub(crate) type MatchersActionReceiver = mpsc::UnboundedReceiver<ActionItem>;
+
+pub(crate) fn channel() -> (MatchersActionSender, MatchersActionReceiver) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    (MatchersActionSender { sender }, receiver)
+}
+
+impl MatchersActionSender {
+    /// 发送 Action，不等待处理结果，Matchers 已停止时返回该 Action
+    pub fn send(&self, action: MatchersAction) -> Result<(), Box<MatchersAction>> {
+        self.sender
+            .send((action, None))
+            .map_err(|e| Box::new((e.0).0))
+    }
+
+    /// 发送 Action 并等待 Matchers 处理
+    ///
+    /// 返回 Action 是否生效（移除、禁用不存在的 Matcher 时为 false），Matchers 已停止时返回 None
+    pub async fn send_ack(&self, action: MatchersAction) -> Option<bool> {
+        let (ack_sender, ack_receiver) = oneshot::channel();
+        self.sender.send((action, Some(ack_sender))).ok()?;
+        ack_receiver.await.ok()
+    }
 }
 
 impl super::matchers::Matchers {
-    /// Matchers 处理 action method
-    pub fn handle_action(&mut self, action: MatchersAction) {
+    /// Matchers 处理 action method，返回 Action 是否生效
+    pub fn handle_action(&mut self, action: MatchersAction) -> bool {
         match action {
             MatchersAction::AddMessageEventMatcher {
                 message_event_matcher,
@@ -30,6 +69,7 @@ impl super::matchers::Matchers {
                     message_event_matcher.name.blue()
                 );
                 self.add_message_matcher(message_event_matcher);
+                true
             }
             MatchersAction::AddNoticeEventMatcher { notice_event_matcher } => {
                 event!(
@@ -38,6 +78,7 @@ impl super::matchers::Matchers {
                     notice_event_matcher.name.blue()
                 );
                 self.add_notice_matcher(notice_event_matcher);
+                true
             }
             MatchersAction::AddRequestEventMatcher { request_event_matcher } => {
                 event!(
@@ -46,15 +87,99 @@ impl super::matchers::Matchers {
                     request_event_matcher.name.blue()
                 );
                 self.add_request_matcher(request_event_matcher);
+                true
             }
             MatchersAction::RemoveMatcher { matcher_name } => {
                 event!(
                     Level::DEBUG,
-                    "Removing Message Event Matcher: {}",
+                    "Removing Matcher: {}",
                     matcher_name.blue()
                 );
-                self.remove_matcher(&matcher_name);
+                self.remove_matcher(&matcher_name)
+            }
+            MatchersAction::DisableMatcher {
+                matcher_name,
+                disable,
+            } => {
+                event!(
+                    Level::DEBUG,
+                    "Setting Matcher {} disable: {}",
+                    matcher_name.blue(),
+                    disable
+                );
+                self.disable_matcher(&matcher_name, disable)
             }
         }
     }
+
+    /// 处理 Action 并回复处理结果
+    pub(crate) fn handle_action_item(&mut self, (action, ack): ActionItem) {
+        let applied = self.handle_action(action);
+        if let Some(ack) = ack {
+            ack.send(applied).ok();
+        }
+    }
+}
+
+#[tokio::test]
+async fn action_test() {
+    use crate::event::MessageEvent;
+
+    struct Noop;
+
+    #[async_trait::async_trait]
+    impl super::Handler<MessageEvent> for Noop {
+        fn match_(&self, _: &mut MessageEvent) -> bool {
+            true
+        }
+
+        async fn handle(&self, _: MessageEvent, _: &mut super::Matcher<MessageEvent>) {}
+    }
+
+    let mut matchers = super::matchers::Matchers::new_empty();
+    let infos = matchers.get_infos();
+    let (sender, mut receiver) = channel();
+
+    // 按发送顺序处理：先添加后禁用
+    let add = MatchersAction::AddMessageEventMatcher {
+        message_event_matcher: super::Matcher::new("noop", Noop),
+    };
+    sender.send(add).unwrap();
+    let disable = MatchersAction::DisableMatcher {
+        matcher_name: "noop".to_string(),
+        disable: true,
+    };
+    sender.send(disable).unwrap();
+    while let Ok(item) = receiver.try_recv() {
+        matchers.handle_action_item(item);
+    }
+    assert!(infos.read().unwrap()["noop"].disable);
+
+    // send_ack 返回 Action 是否生效
+    let acks = tokio::spawn({
+        let sender = sender.clone();
+        async move {
+            let remove = |name: &str| MatchersAction::RemoveMatcher {
+                matcher_name: name.to_string(),
+            };
+            (
+                sender.send_ack(remove("noop")).await,
+                sender.send_ack(remove("noop")).await,
+            )
+        }
+    });
+    for _ in 0..2 {
+        let item = receiver.recv().await.unwrap();
+        matchers.handle_action_item(item);
+    }
+    assert_eq!(acks.await.unwrap(), (Some(true), Some(false)));
+    assert!(!infos.read().unwrap().contains_key("noop"));
+
+    // Matchers 停止后发送失败
+    drop(receiver);
+    let remove = MatchersAction::RemoveMatcher {
+        matcher_name: "noop".to_string(),
+    };
+    assert!(sender.send(remove.clone()).is_err());
+    assert_eq!(sender.send_ack(remove).await, None);
 }
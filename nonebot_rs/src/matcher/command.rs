@@ -0,0 +1,790 @@
+use super::{Handler, Matcher};
+use crate::event::MessageEvent;
+use crate::utils::remove_space;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 命令解析错误类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandErrorKind {
+    /// 消息不是该命令
+    NotMatched,
+    /// 引号未闭合
+    UnclosedQuote,
+    /// 未知子命令
+    UnknownSubCommand(String),
+    /// 缺少子命令
+    MissingSubCommand,
+    /// 未知选项
+    UnknownOption(String),
+    /// 选项缺少值
+    MissingOptionValue(String),
+    /// 缺少参数
+    MissingArgument(String),
+    /// 参数过多
+    TooManyArguments(Vec<String>),
+    /// 参数值无法解析为目标类型
+    InvalidValue { name: String, value: String },
+    /// 参数值不在可选值中
+    InvalidChoice {
+        name: String,
+        value: String,
+        choices: Vec<String>,
+    },
+    /// 参数值未通过校验
+    Rejected { name: String, reason: String },
+}
+
+impl std::fmt::Display for CommandErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandErrorKind::NotMatched => write!(f, "命令不匹配"),
+            CommandErrorKind::UnclosedQuote => write!(f, "引号未闭合"),
+            CommandErrorKind::UnknownSubCommand(name) => write!(f, "未知子命令 {}", name),
+            CommandErrorKind::MissingSubCommand => write!(f, "缺少子命令"),
+            CommandErrorKind::UnknownOption(name) => write!(f, "未知选项 {}", name),
+            CommandErrorKind::MissingOptionValue(name) => write!(f, "选项 {} 缺少值", name),
+            CommandErrorKind::MissingArgument(name) => write!(f, "缺少参数 <{}>", name),
+            CommandErrorKind::TooManyArguments(args) => {
+                write!(f, "多余的参数 {}", args.join(" "))
+            }
+            CommandErrorKind::InvalidValue { name, value } => {
+                write!(f, "参数 {} 的值 {} 无效", name, value)
+            }
+            CommandErrorKind::InvalidChoice {
+                name,
+                value,
+                choices,
+            } => write!(
+                f,
+                "参数 {} 的值 {} 无效，可选值: {}",
+                name,
+                value,
+                choices.join("|")
+            ),
+            CommandErrorKind::Rejected { name, reason } => {
+                write!(f, "参数 {} 无效: {}", name, reason)
+            }
+        }
+    }
+}
+
+/// 命令解析错误，附带出错命令的用法
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    pub kind: CommandErrorKind,
+    pub usage: String,
+}
+
+impl CommandError {
+    pub fn new(kind: CommandErrorKind, usage: String) -> Self {
+        CommandError { kind, usage }
+    }
+
+    /// 是否仅为命令不匹配
+    pub fn is_not_matched(&self) -> bool {
+        self.kind == CommandErrorKind::NotMatched
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.usage.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}\n用法: {}", self.kind, self.usage)
+        }
+    }
+}
+
+impl From<CommandError> for crate::NBError {
+    fn from(value: CommandError) -> Self {
+        crate::NBError::Text(value.to_string())
+    }
+}
+
+/// 按 shell 规则切分命令文本
+///
+/// 支持空白分隔、单双引号包裹与 `\` 转义
+pub fn shell_split(text: &str) -> Result<Vec<String>, CommandErrorKind> {
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) => {
+                if c == q {
+                    quote = None;
+                } else if c == '\\' && q == '"' {
+                    match chars.next() {
+                        Some(n) => current.push(n),
+                        None => return Err(CommandErrorKind::UnclosedQuote),
+                    }
+                } else {
+                    current.push(c);
+                }
+            }
+            None => {
+                if c.is_whitespace() {
+                    if in_token {
+                        tokens.push(std::mem::take(&mut current));
+                        in_token = false;
+                    }
+                } else if c == '"' || c == '\'' {
+                    quote = Some(c);
+                    in_token = true;
+                } else if c == '\\' {
+                    if let Some(n) = chars.next() {
+                        current.push(n);
+                    }
+                    in_token = true;
+                } else {
+                    current.push(c);
+                    in_token = true;
+                }
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(CommandErrorKind::UnclosedQuote);
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+/// 位置参数声明
+#[derive(Debug, Clone)]
+pub struct ArgSpec {
+    pub name: String,
+    pub required: bool,
+    /// 是否接收剩余所有参数，只能是最后一个参数
+    pub variadic: bool,
+}
+
+impl ArgSpec {
+    /// 必需参数 `<name>`
+    pub fn required(name: &str) -> Self {
+        ArgSpec {
+            name: name.to_string(),
+            required: true,
+            variadic: false,
+        }
+    }
+
+    /// 可选参数 `[name]`
+    pub fn optional(name: &str) -> Self {
+        ArgSpec {
+            name: name.to_string(),
+            required: false,
+            variadic: false,
+        }
+    }
+
+    /// 接收剩余所有参数 `[name...]`
+    pub fn variadic(name: &str) -> Self {
+        ArgSpec {
+            name: name.to_string(),
+            required: false,
+            variadic: true,
+        }
+    }
+
+    fn usage(&self) -> String {
+        match (self.required, self.variadic) {
+            (true, false) => format!("<{}>", self.name),
+            (true, true) => format!("<{}...>", self.name),
+            (false, false) => format!("[{}]", self.name),
+            (false, true) => format!("[{}...]", self.name),
+        }
+    }
+}
+
+/// 选项声明，`--name` / `-s`
+#[derive(Debug, Clone)]
+pub struct OptionSpec {
+    pub name: String,
+    pub short: Option<char>,
+    /// 是否需要值 `--name value` / `--name=value` / `-s value`
+    pub takes_value: bool,
+}
+
+impl OptionSpec {
+    /// 开关选项
+    pub fn flag(name: &str, short: Option<char>) -> Self {
+        OptionSpec {
+            name: name.to_string(),
+            short,
+            takes_value: false,
+        }
+    }
+
+    /// 带值选项
+    pub fn value(name: &str, short: Option<char>) -> Self {
+        OptionSpec {
+            name: name.to_string(),
+            short,
+            takes_value: true,
+        }
+    }
+
+    fn usage(&self) -> String {
+        let head = match self.short {
+            Some(s) => format!("-{}|--{}", s, self.name),
+            None => format!("--{}", self.name),
+        };
+        if self.takes_value {
+            format!("[{} <{}>]", head, self.name)
+        } else {
+            format!("[{}]", head)
+        }
+    }
+}
+
+/// 命令声明，可包含别名、子命令、位置参数与选项
+#[derive(Debug, Clone)]
+pub struct CommandSpec {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub args: Vec<ArgSpec>,
+    pub options: Vec<OptionSpec>,
+    pub sub_commands: Vec<CommandSpec>,
+}
+
+impl CommandSpec {
+    pub fn new(name: &str) -> Self {
+        CommandSpec {
+            name: name.to_string(),
+            aliases: vec![],
+            args: vec![],
+            options: vec![],
+            sub_commands: vec![],
+        }
+    }
+
+    /// 添加别名
+    pub fn add_alias(mut self, alias: &str) -> Self {
+        self.aliases.push(alias.to_string());
+        self
+    }
+
+    /// 添加位置参数
+    pub fn add_arg(mut self, arg: ArgSpec) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// 添加选项
+    pub fn add_option(mut self, option: OptionSpec) -> Self {
+        self.options.push(option);
+        self
+    }
+
+    /// 添加子命令
+    pub fn add_sub_command(mut self, sub_command: CommandSpec) -> Self {
+        self.sub_commands.push(sub_command);
+        self
+    }
+
+    /// 名称或别名是否匹配
+    pub fn is_name(&self, name: &str) -> bool {
+        self.name == name || self.aliases.iter().any(|a| a == name)
+    }
+
+    /// 名称与所有别名
+    pub fn names(&self) -> Vec<String> {
+        let mut names = vec![self.name.clone()];
+        names.extend(self.aliases.iter().cloned());
+        names
+    }
+
+    /// 仅检查消息首个词是否为该命令
+    pub fn is_match(&self, text: &str) -> bool {
+        match remove_space(text).split_whitespace().next() {
+            Some(head) => self.is_name(head),
+            None => false,
+        }
+    }
+
+    fn usage_line(&self, path: &[&CommandSpec]) -> String {
+        let mut parts: Vec<String> = path.iter().map(|c| c.name.clone()).collect();
+        if !self.sub_commands.is_empty() {
+            let subs: Vec<&str> = self.sub_commands.iter().map(|c| c.name.as_str()).collect();
+            parts.push(format!("<{}>", subs.join("|")));
+        }
+        parts.extend(self.args.iter().map(|a| a.usage()));
+        parts.extend(self.options.iter().map(|o| o.usage()));
+        parts.join(" ")
+    }
+
+    /// 生成用法说明
+    pub fn usage(&self) -> String {
+        self.usage_line(&[self])
+    }
+
+    /// 解析命令文本
+    pub fn parse(&self, text: &str) -> Result<ParsedCommand, CommandError> {
+        let tokens = shell_split(text).map_err(|kind| {
+            if self.is_match(text) {
+                CommandError::new(kind, self.usage())
+            } else {
+                CommandError::new(CommandErrorKind::NotMatched, String::new())
+            }
+        })?;
+        let mut tokens = tokens.into_iter();
+        match tokens.next() {
+            Some(head) if self.is_name(&head) => {}
+            _ => {
+                return Err(CommandError::new(
+                    CommandErrorKind::NotMatched,
+                    String::new(),
+                ))
+            }
+        }
+        let mut tokens: Vec<String> = tokens.collect();
+
+        // 逐级进入子命令
+        let mut path = vec![self];
+        let mut current = self;
+        loop {
+            if current.sub_commands.is_empty() {
+                break;
+            }
+            let next = tokens
+                .first()
+                .and_then(|t| current.sub_commands.iter().find(|c| c.is_name(t)));
+            match next {
+                Some(sub) => {
+                    tokens.remove(0);
+                    path.push(sub);
+                    current = sub;
+                }
+                None => {
+                    if !current.args.is_empty() {
+                        break;
+                    }
+                    let kind = match tokens.first() {
+                        Some(t) if !t.starts_with('-') => {
+                            CommandErrorKind::UnknownSubCommand(t.clone())
+                        }
+                        _ => CommandErrorKind::MissingSubCommand,
+                    };
+                    return Err(CommandError::new(kind, current.usage_line(&path)));
+                }
+            }
+        }
+        let usage = current.usage_line(&path);
+        let err = |kind| CommandError::new(kind, usage.clone());
+
+        // 分离选项与位置参数
+        let (positional, options) = split_options(tokens, &current.options).map_err(err)?;
+
+        // 按声明顺序分配位置参数
+        let mut args = HashMap::new();
+        let mut positional = positional.into_iter();
+        for arg in &current.args {
+            if arg.variadic {
+                let rest: Vec<String> = positional.by_ref().collect();
+                if arg.required && rest.is_empty() {
+                    return Err(err(CommandErrorKind::MissingArgument(arg.name.clone())));
+                }
+                args.insert(arg.name.clone(), rest);
+                continue;
+            }
+            match positional.next() {
+                Some(v) => {
+                    args.insert(arg.name.clone(), vec![v]);
+                }
+                None if arg.required => {
+                    return Err(err(CommandErrorKind::MissingArgument(arg.name.clone())))
+                }
+                None => {}
+            }
+        }
+        let extra: Vec<String> = positional.collect();
+        if !extra.is_empty() {
+            return Err(err(CommandErrorKind::TooManyArguments(extra)));
+        }
+
+        Ok(ParsedCommand {
+            path: path.iter().map(|c| c.name.clone()).collect(),
+            args,
+            options,
+            usage,
+        })
+    }
+}
+
+/// 选项名与值，开关选项的值为 None
+type Options = HashMap<String, Option<String>>;
+
+/// 按选项声明分离选项与位置参数，`--` 之后均视为位置参数
+fn split_options(
+    tokens: Vec<String>,
+    specs: &[OptionSpec],
+) -> Result<(Vec<String>, Options), CommandErrorKind> {
+    let mut options = Options::new();
+    let mut positional = vec![];
+    let mut tokens = tokens.into_iter();
+    let mut only_positional = false;
+    while let Some(token) = tokens.next() {
+        if only_positional || !is_option_like(&token) {
+            positional.push(token);
+            continue;
+        }
+        if token == "--" {
+            only_positional = true;
+            continue;
+        }
+        let long = match token.strip_prefix("--") {
+            Some(long) => long,
+            None => {
+                split_short_options(&token, &mut tokens, specs, &mut options)?;
+                continue;
+            }
+        };
+        let (name, inline_value) = match long.split_once('=') {
+            Some((n, v)) => (n, Some(v.to_string())),
+            None => (long, None),
+        };
+        let spec = match specs.iter().find(|o| o.name == name) {
+            Some(o) => o,
+            None => return Err(CommandErrorKind::UnknownOption(token.clone())),
+        };
+        let value = if spec.takes_value {
+            match inline_value.or_else(|| tokens.next()) {
+                Some(v) => Some(v),
+                None => return Err(CommandErrorKind::MissingOptionValue(spec.name.clone())),
+            }
+        } else {
+            None
+        };
+        options.insert(spec.name.clone(), value);
+    }
+    Ok((positional, options))
+}
+
+/// 展开短选项，`-vx` 依次为多个开关，需要值的短选项以其后的剩余部分或下一个词为值，如 `-vrspam`
+fn split_short_options(
+    token: &str,
+    tokens: &mut impl Iterator<Item = String>,
+    specs: &[OptionSpec],
+    options: &mut Options,
+) -> Result<(), CommandErrorKind> {
+    for (i, short) in token.char_indices().skip(1) {
+        let spec = match specs.iter().find(|o| o.short == Some(short)) {
+            Some(o) => o,
+            None => return Err(CommandErrorKind::UnknownOption(format!("-{}", short))),
+        };
+        if !spec.takes_value {
+            options.insert(spec.name.clone(), None);
+            continue;
+        }
+        let rest = &token[i + short.len_utf8()..];
+        let value = if rest.is_empty() {
+            tokens.next()
+        } else {
+            Some(rest.to_string())
+        };
+        if value.is_none() {
+            return Err(CommandErrorKind::MissingOptionValue(spec.name.clone()));
+        }
+        options.insert(spec.name.clone(), value);
+        break;
+    }
+    Ok(())
+}
+
+/// 以 `-` 开头且不是负数的词视为选项
+fn is_option_like(token: &str) -> bool {
+    token.len() > 1 && token.starts_with('-') && token.parse::<f64>().is_err()
+}
+
+/// 命令解析结果
+#[derive(Debug, Clone)]
+pub struct ParsedCommand {
+    /// 命令路径（主命令名与子命令名，不含别名）
+    pub path: Vec<String>,
+    args: HashMap<String, Vec<String>>,
+    options: HashMap<String, Option<String>>,
+    usage: String,
+}
+
+impl ParsedCommand {
+    /// 匹配到的最深一级子命令名
+    pub fn sub_command(&self) -> &str {
+        self.path.last().map(|s| s.as_str()).unwrap_or("")
+    }
+
+    /// 当前命令用法
+    pub fn usage(&self) -> &str {
+        &self.usage
+    }
+
+    fn parse_value<T: std::str::FromStr>(&self, name: &str, value: &str) -> Result<T, CommandError> {
+        value.parse::<T>().map_err(|_| {
+            CommandError::new(
+                CommandErrorKind::InvalidValue {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                },
+                self.usage.clone(),
+            )
+        })
+    }
+
+    /// 获取可选位置参数
+    pub fn arg<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, CommandError> {
+        match self.args.get(name).and_then(|v| v.first()) {
+            Some(v) => self.parse_value(name, v).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 获取必需位置参数
+    pub fn require<T: std::str::FromStr>(&self, name: &str) -> Result<T, CommandError> {
+        match self.arg(name)? {
+            Some(v) => Ok(v),
+            None => Err(CommandError::new(
+                CommandErrorKind::MissingArgument(name.to_string()),
+                self.usage.clone(),
+            )),
+        }
+    }
+
+    /// 获取 variadic 参数
+    pub fn args<T: std::str::FromStr>(&self, name: &str) -> Result<Vec<T>, CommandError> {
+        match self.args.get(name) {
+            Some(v) => v.iter().map(|s| self.parse_value(name, s)).collect(),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// 开关选项是否出现
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    /// 获取带值选项
+    pub fn option<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, CommandError> {
+        match self.options.get(name) {
+            Some(Some(v)) => self.parse_value(name, v).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// 将参数值解析为目标类型
+///
+/// 目标类型的 `FromStr::Err` 为 `CommandErrorKind` 时（如 `#[derive(FromCommand)]` 的枚举）
+/// 保留其错误并补全参数名，其余解析失败均为 `InvalidValue`
+pub fn parse_value<T>(name: &str, value: &str) -> Result<T, CommandErrorKind>
+where
+    T: std::str::FromStr,
+    T::Err: 'static,
+{
+    value.parse::<T>().map_err(|e| {
+        match (&e as &dyn std::any::Any).downcast_ref::<CommandErrorKind>() {
+            Some(CommandErrorKind::InvalidChoice { value, choices, .. }) => {
+                CommandErrorKind::InvalidChoice {
+                    name: name.to_string(),
+                    value: value.clone(),
+                    choices: choices.clone(),
+                }
+            }
+            _ => CommandErrorKind::InvalidValue {
+                name: name.to_string(),
+                value: value.to_string(),
+            },
+        }
+    })
+}
+
+/// `#[derive(FromCommand)]` 使用的参数表，命令文本按 shell 规则切分后分离出位置参数与选项
+#[derive(Debug, Clone)]
+pub struct CommandArgs {
+    positional: std::collections::VecDeque<String>,
+    options: HashMap<String, Option<String>>,
+}
+
+impl CommandArgs {
+    pub fn parse(text: &str, options: &[OptionSpec]) -> Result<Self, CommandErrorKind> {
+        let (positional, options) = split_options(shell_split(text)?, options)?;
+        Ok(CommandArgs {
+            positional: positional.into(),
+            options,
+        })
+    }
+
+    /// 取下一个位置参数，没有剩余参数时为 None
+    pub fn next<T>(&mut self, name: &str) -> Result<Option<T>, CommandErrorKind>
+    where
+        T: std::str::FromStr,
+        T::Err: 'static,
+    {
+        match self.positional.pop_front() {
+            Some(v) => parse_value(name, &v).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// 取下一个必需位置参数
+    pub fn require<T>(&mut self, name: &str) -> Result<T, CommandErrorKind>
+    where
+        T: std::str::FromStr,
+        T::Err: 'static,
+    {
+        self.next(name)?
+            .ok_or_else(|| CommandErrorKind::MissingArgument(name.to_string()))
+    }
+
+    /// 取剩余所有位置参数
+    pub fn rest<T>(&mut self, name: &str) -> Result<Vec<T>, CommandErrorKind>
+    where
+        T: std::str::FromStr,
+        T::Err: 'static,
+    {
+        self.positional
+            .drain(..)
+            .map(|v| parse_value(name, &v))
+            .collect()
+    }
+
+    /// 开关选项是否出现
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.contains_key(name)
+    }
+
+    /// 获取带值选项
+    pub fn option<T>(&self, name: &str) -> Result<Option<T>, CommandErrorKind>
+    where
+        T: std::str::FromStr,
+        T::Err: 'static,
+    {
+        match self.options.get(name) {
+            Some(Some(v)) => parse_value(name, v).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// 检查位置参数已全部取出
+    pub fn finish(self) -> Result<(), CommandErrorKind> {
+        if self.positional.is_empty() {
+            Ok(())
+        } else {
+            Err(CommandErrorKind::TooManyArguments(self.positional.into()))
+        }
+    }
+}
+
+/// 命令树处理函数 trait
+#[async_trait]
+pub trait CommandHandler {
+    async fn handle(
+        &self,
+        command: ParsedCommand,
+        event: MessageEvent,
+        matcher: &mut Matcher<MessageEvent>,
+    ) -> crate::NBResult<()>;
+}
+
+/// 将 `CommandSpec` 与 `CommandHandler` 组合为 `Handler<MessageEvent>`
+///
+/// 命令名或别名匹配后即视为匹配成功，参数错误时回复错误信息与用法
+pub struct OnCommand<H> {
+    spec: Arc<CommandSpec>,
+    handler: H,
+}
+
+impl<H> OnCommand<H>
+where
+    H: CommandHandler + Send + Sync + 'static,
+{
+    pub fn new(spec: CommandSpec, handler: H) -> Self {
+        OnCommand {
+            spec: Arc::new(spec),
+            handler,
+        }
+    }
+
+    /// 构建对应 Matcher，name 为主命令名
+    pub fn matcher(spec: CommandSpec, handler: H) -> Matcher<MessageEvent> {
+        let name = spec.name.clone();
+        Matcher::new(&name, OnCommand::new(spec, handler))
+    }
+}
+
+#[async_trait]
+impl<H> Handler<MessageEvent> for OnCommand<H>
+where
+    H: CommandHandler + Send + Sync + 'static,
+{
+    fn match_(&self, event: &mut MessageEvent) -> bool {
+        self.spec.is_match(event.get_raw_message())
+    }
+
+    fn meta(&self) -> super::MatcherMeta {
+        super::MatcherMeta {
+            commands: self.spec.names(),
+            usage: Some(self.spec.usage()),
+            ..Default::default()
+        }
+    }
+
+    async fn handle(&self, event: MessageEvent, matcher: &mut Matcher<MessageEvent>) {
+        let result = match self.spec.parse(event.get_raw_message()) {
+            Ok(command) => self.handler.handle(command, event, matcher).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(err) = result {
+            matcher.report_error(err).await;
+        }
+    }
+}
+
+#[test]
+fn parse_test() {
+    let spec = CommandSpec::new("admin").add_alias("adm").add_sub_command(
+        CommandSpec::new("ban")
+            .add_arg(ArgSpec::required("user"))
+            .add_arg(ArgSpec::optional("minutes"))
+            .add_option(OptionSpec::value("reason", Some('r')))
+            .add_option(OptionSpec::flag("silent", Some('s'))),
+    );
+    let parsed = spec
+        .parse(r#"adm ban 123 -5 --reason "too much spam" -s"#)
+        .unwrap();
+    assert_eq!(parsed.path, vec!["admin", "ban"]);
+    assert_eq!(parsed.require::<i64>("user").unwrap(), 123);
+    assert_eq!(parsed.arg::<i64>("minutes").unwrap(), Some(-5));
+    assert_eq!(parsed.option::<String>("reason").unwrap().unwrap(), "too much spam");
+    assert!(parsed.flag("silent"));
+    let parsed = spec.parse("admin ban 123 -sr spam").unwrap();
+    assert!(parsed.flag("silent"));
+    assert_eq!(parsed.option::<String>("reason").unwrap().unwrap(), "spam");
+    let parsed = spec.parse("admin ban 123 -srspam").unwrap();
+    assert_eq!(parsed.option::<String>("reason").unwrap().unwrap(), "spam");
+    assert_eq!(
+        spec.parse("admin ban 123 -sx").unwrap_err().kind,
+        CommandErrorKind::UnknownOption("-x".to_string())
+    );
+    assert_eq!(
+        spec.parse("admin ban 123 -sr").unwrap_err().kind,
+        CommandErrorKind::MissingOptionValue("reason".to_string())
+    );
+    assert!(spec.parse("echo hi").unwrap_err().is_not_matched());
+    assert_eq!(
+        spec.parse("admin ban").unwrap_err().kind,
+        CommandErrorKind::MissingArgument("user".to_string())
+    );
+    assert_eq!(
+        spec.parse("admin kick 1").unwrap_err().kind,
+        CommandErrorKind::UnknownSubCommand("kick".to_string())
+    );
+}
@@ -0,0 +1,311 @@
+use super::{shell_split, Matcher, Session};
+use crate::event::MessageEvent;
+use crate::{NBError, NBResult};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 参数校验函数类型，校验失败返回的文本将替换 reject 中的 `{error}`
+pub type Validator = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// 会话中待填写的单个参数
+#[derive(Clone)]
+pub struct Slot {
+    pub name: String,
+    /// 请求该参数时发送的提示
+    pub prompt: Option<String>,
+    /// 校验失败时发送的提示，`{error}` 替换为校验错误
+    pub reject: Option<String>,
+    /// 等待该参数的超时（秒），未设置时使用会话超时
+    pub timeout: Option<i64>,
+    validator: Option<Validator>,
+}
+
+impl std::fmt::Debug for Slot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Slot")
+            .field("name", &self.name)
+            .field("prompt", &self.prompt)
+            .field("reject", &self.reject)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl Slot {
+    pub fn new(name: &str) -> Self {
+        Slot {
+            name: name.to_string(),
+            prompt: None,
+            reject: None,
+            timeout: None,
+            validator: None,
+        }
+    }
+
+    /// 设置请求提示
+    pub fn prompt(mut self, prompt: &str) -> Self {
+        self.prompt = Some(prompt.to_string());
+        self
+    }
+
+    /// 设置校验失败提示
+    pub fn reject(mut self, reject: &str) -> Self {
+        self.reject = Some(reject.to_string());
+        self
+    }
+
+    /// 设置等待超时（秒）
+    pub fn timeout(mut self, timeout: i64) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 设置校验函数
+    pub fn validate<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// 要求参数可解析为 T
+    pub fn parse<T>(self) -> Self
+    where
+        T: std::str::FromStr,
+    {
+        self.validate(|value| {
+            value
+                .parse::<T>()
+                .map(|_| ())
+                .map_err(|_| format!("无法识别的值: {}", value))
+        })
+    }
+
+    fn check(&self, value: &str) -> Result<(), String> {
+        match &self.validator {
+            Some(validator) => validator(value),
+            None => Ok(()),
+        }
+    }
+
+    fn reject_text(&self, error: &str) -> String {
+        match &self.reject {
+            Some(reject) => reject.replace("{error}", error),
+            None => format!("{}，请重新输入", error),
+        }
+    }
+}
+
+/// 多步会话，依次向用户请求各参数
+///
+/// ``` rust,ignore
+/// let conversation = Conversation::new()
+///     .got(Slot::new("city").prompt("你想查询哪个城市？"))
+///     .got(Slot::new("days").prompt("查询几天？").parse::<u8>().reject("{error}，请输入数字"));
+/// let slots = conversation.run(matcher, Slots::positional(&conversation, args)).await?;
+/// let days: u8 = slots.require("days")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    pub slots: Vec<Slot>,
+    /// 取消会话的关键词
+    pub cancel_keywords: Vec<String>,
+    /// 取消时发送的提示
+    pub cancel_reply: Option<String>,
+    /// 超时时发送的提示
+    pub timeout_reply: Option<String>,
+    /// 默认等待超时（秒）
+    pub timeout: i64,
+}
+
+impl Default for Conversation {
+    fn default() -> Self {
+        Conversation {
+            slots: vec![],
+            cancel_keywords: vec!["取消".to_string(), "cancel".to_string()],
+            cancel_reply: Some("已取消".to_string()),
+            timeout_reply: None,
+            timeout: 60,
+        }
+    }
+}
+
+impl Conversation {
+    pub fn new() -> Self {
+        Conversation::default()
+    }
+
+    /// 添加参数
+    pub fn got(mut self, slot: Slot) -> Self {
+        self.slots.push(slot);
+        self
+    }
+
+    /// 设置取消关键词
+    pub fn set_cancel_keywords(mut self, keywords: &[&str]) -> Self {
+        self.cancel_keywords = keywords.iter().map(|k| k.to_string()).collect();
+        self
+    }
+
+    /// 设置取消提示，None 表示不提示
+    pub fn set_cancel_reply(mut self, reply: Option<&str>) -> Self {
+        self.cancel_reply = reply.map(|r| r.to_string());
+        self
+    }
+
+    /// 设置超时提示，None 表示不提示
+    pub fn set_timeout_reply(mut self, reply: Option<&str>) -> Self {
+        self.timeout_reply = reply.map(|r| r.to_string());
+        self
+    }
+
+    /// 设置默认等待超时（秒）
+    pub fn set_timeout(mut self, timeout: i64) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn is_cancel(&self, text: &str) -> bool {
+        self.cancel_keywords.iter().any(|k| k == text)
+    }
+
+    /// 执行会话，已存在于 preset 中且校验通过的参数不会再次请求
+    ///
+    /// 用户取消返回 `NBError::State(Session::Cancel)`，超时返回 `NBError::State(Session::Timeout)`
+    pub async fn run(
+        &self,
+        matcher: &Matcher<MessageEvent>,
+        mut preset: Slots,
+    ) -> NBResult<Slots> {
+        let mut slots = Slots::default();
+        for slot in &self.slots {
+            if let Some(value) = preset.values.remove(&slot.name) {
+                match slot.check(&value) {
+                    Ok(()) => {
+                        slots.values.insert(slot.name.clone(), value);
+                        continue;
+                    }
+                    Err(error) => matcher.send_text(&slot.reject_text(&error)).await,
+                }
+            }
+            let value = self.ask(matcher, slot).await?;
+            slots.values.insert(slot.name.clone(), value);
+        }
+        Ok(slots)
+    }
+
+    async fn ask(&self, matcher: &Matcher<MessageEvent>, slot: &Slot) -> NBResult<String> {
+        let mut prompt = slot
+            .prompt
+            .as_ref()
+            .map(|p| vec![crate::message::Message::text(p)]);
+        loop {
+            let timeout = slot.timeout.unwrap_or(self.timeout);
+            let text = match matcher
+                .request_message_callback(timeout, None, prompt.take(), |_, _, _| {
+                    Box::pin(async { true })
+                })
+                .await
+            {
+                Ok((text, _)) => text,
+                Err(NBError::State(Session::Timeout)) => {
+                    if let Some(reply) = &self.timeout_reply {
+                        matcher.send_text(reply).await;
+                    }
+                    return Err(NBError::State(Session::Timeout));
+                }
+                Err(e) => return Err(e),
+            };
+            if self.is_cancel(&text) {
+                if let Some(reply) = &self.cancel_reply {
+                    matcher.send_text(reply).await;
+                }
+                return Err(NBError::State(Session::Cancel));
+            }
+            match slot.check(&text) {
+                Ok(()) => return Ok(text),
+                Err(error) => {
+                    prompt = Some(vec![crate::message::Message::text(slot.reject_text(&error))])
+                }
+            }
+        }
+    }
+}
+
+/// 已填写的会话参数
+#[derive(Debug, Clone, Default)]
+pub struct Slots {
+    values: HashMap<String, String>,
+}
+
+impl Slots {
+    /// 预设参数
+    pub fn set(mut self, name: &str, value: &str) -> Self {
+        self.values.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// 按会话参数顺序从触发消息的剩余文本中依次预设参数
+    pub fn positional(conversation: &Conversation, text: &str) -> Self {
+        let words = shell_split(text).unwrap_or_default();
+        let mut slots = Slots::default();
+        for (slot, word) in conversation.slots.iter().zip(words) {
+            slots.values.insert(slot.name.clone(), word);
+        }
+        slots
+    }
+
+    /// 获取参数原文
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(|v| v.as_str())
+    }
+
+    /// 获取参数并解析为 T
+    pub fn require<T>(&self, name: &str) -> NBResult<T>
+    where
+        T: std::str::FromStr,
+    {
+        let value = self
+            .get(name)
+            .ok_or_else(|| NBError::from(format!("缺少参数 {}", name)))?;
+        value
+            .parse::<T>()
+            .map_err(|_| format!("参数 {} 的值 {} 无效", name, value).into())
+    }
+}
+
+impl Matcher<MessageEvent> {
+    /// 执行多步会话，参见 `Conversation::run`
+    pub async fn got(&self, conversation: &Conversation, preset: Slots) -> NBResult<Slots> {
+        conversation.run(self, preset).await
+    }
+}
+
+#[test]
+fn conversation_test() {
+    let conversation = Conversation::new()
+        .got(Slot::new("city"))
+        .got(Slot::new("days").parse::<u8>().reject("{error}，请输入数字"))
+        .set_cancel_keywords(&["算了"]);
+    assert!(conversation.is_cancel("算了"));
+    assert!(!conversation.is_cancel("取消"));
+
+    let days = &conversation.slots[1];
+    assert!(days.check("3").is_ok());
+    let error = days.check("three").unwrap_err();
+    assert_eq!(days.reject_text(&error), "无法识别的值: three，请输入数字");
+    assert_eq!(
+        conversation.slots[0].reject_text("太长了"),
+        "太长了，请重新输入"
+    );
+
+    // 多余的词被忽略，引号内视为一个参数
+    let slots = Slots::positional(&conversation, "\"New York\" 3 extra");
+    assert_eq!(slots.get("city"), Some("New York"));
+    assert_eq!(slots.require::<u8>("days").unwrap(), 3);
+    let slots = Slots::positional(&conversation, "上海").set("days", "x");
+    assert_eq!(slots.get("city"), Some("上海"));
+    assert!(slots.require::<u8>("days").is_err());
+    assert!(slots.require::<String>("missing").is_err());
+}
@@ -0,0 +1,163 @@
+use crate::event::{GroupId, SelfId, UserId};
+use crate::utils::timestamp;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 冷却作用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CooldownScope {
+    /// 每个用户独立冷却
+    User,
+    /// 每个群独立冷却（私聊按用户冷却）
+    Group,
+    /// 所有会话共享冷却
+    Global,
+}
+
+impl std::str::FromStr for CooldownScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(CooldownScope::User),
+            "group" => Ok(CooldownScope::Group),
+            "global" => Ok(CooldownScope::Global),
+            _ => Err(format!("unknown cooldown scope: {}", s)),
+        }
+    }
+}
+
+/// Matcher 冷却设置
+///
+/// 冷却期间匹配成功的事件不会进入 handler，若设置了 reply 则回复提示消息，
+/// reply 中的 `{remaining}` 会被替换为剩余冷却秒数
+#[derive(Clone)]
+pub struct Cooldown {
+    /// 冷却作用范围
+    pub scope: CooldownScope,
+    /// 冷却时长（秒）
+    pub seconds: i64,
+    /// 冷却中的回复
+    pub reply: Option<String>,
+    /// 各会话上次触发时间戳
+    records: Arc<Mutex<HashMap<(i64, i64), i64>>>,
+}
+
+impl std::fmt::Debug for Cooldown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Cooldown")
+            .field("scope", &self.scope)
+            .field("seconds", &self.seconds)
+            .field("reply", &self.reply)
+            .finish()
+    }
+}
+
+impl Cooldown {
+    pub fn new(scope: CooldownScope, seconds: i64) -> Self {
+        Cooldown {
+            scope,
+            seconds,
+            reply: None,
+            records: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 设置冷却中的回复
+    pub fn set_reply(mut self, reply: &str) -> Self {
+        self.reply = Some(reply.to_string());
+        self
+    }
+
+    fn key<E>(&self, event: &E) -> (i64, i64)
+    where
+        E: SelfId + UserId + GroupId,
+    {
+        let id = match self.scope {
+            CooldownScope::User => event.get_user_id(),
+            CooldownScope::Group => match event.get_group_id() {
+                0 => -event.get_user_id(),
+                group_id => group_id,
+            },
+            CooldownScope::Global => 0,
+        };
+        (event.get_self_id(), id)
+    }
+
+    /// 检查 event 所在会话是否冷却中，冷却中返回剩余秒数，否则记录本次触发
+    pub fn check<E>(&self, event: &E) -> Option<i64>
+    where
+        E: SelfId + UserId + GroupId,
+    {
+        let key = self.key(event);
+        let now = timestamp();
+        let mut records = self.records.lock().unwrap();
+        if let Some(last) = records.get(&key) {
+            let remaining = last + self.seconds - now;
+            if remaining > 0 {
+                return Some(remaining);
+            }
+        }
+        records.insert(key, now);
+        // 顺带清理已过期记录，避免长期运行后无限增长
+        if records.len() > 1024 {
+            let seconds = self.seconds;
+            records.retain(|_, last| *last + seconds > now);
+        }
+        None
+    }
+
+    /// 生成冷却中的回复文本
+    pub fn reply_text(&self, remaining: i64) -> Option<String> {
+        self.reply
+            .as_ref()
+            .map(|r| r.replace("{remaining}", &remaining.to_string()))
+    }
+}
+
+#[test]
+fn cooldown_test() {
+    /// (self_id, user_id, group_id)
+    struct Ids(i64, i64, i64);
+    impl SelfId for Ids {
+        fn get_self_id(&self) -> i64 {
+            self.0
+        }
+    }
+    impl UserId for Ids {
+        fn get_user_id(&self) -> i64 {
+            self.1
+        }
+    }
+    impl GroupId for Ids {
+        fn get_group_id(&self) -> i64 {
+            self.2
+        }
+    }
+
+    let user = Cooldown::new(CooldownScope::User, 60).set_reply("还需 {remaining} 秒");
+    assert_eq!(user.check(&Ids(1, 10, 100)), None);
+    assert!(user.check(&Ids(1, 10, 200)).is_some());
+    assert_eq!(user.check(&Ids(1, 11, 100)), None);
+    // 不同 Bot 互不影响
+    assert_eq!(user.check(&Ids(2, 10, 100)), None);
+    assert_eq!(user.reply_text(5), Some("还需 5 秒".to_string()));
+
+    let group = Cooldown::new(CooldownScope::Group, 60);
+    assert_eq!(group.check(&Ids(1, 10, 100)), None);
+    assert!(group.check(&Ids(1, 11, 100)).is_some());
+    // 私聊按用户冷却，不与群号冲突
+    assert_eq!(group.check(&Ids(1, 100, 0)), None);
+    assert!(group.check(&Ids(1, 100, 0)).is_some());
+    assert_eq!(group.check(&Ids(1, 101, 0)), None);
+
+    let global = Cooldown::new(CooldownScope::Global, 60);
+    assert_eq!(global.check(&Ids(1, 10, 100)), None);
+    assert!(global.check(&Ids(1, 11, 0)).is_some());
+    assert_eq!(global.reply_text(5), None);
+
+    let expired = Cooldown::new(CooldownScope::Global, 0);
+    assert_eq!(expired.check(&Ids(1, 10, 100)), None);
+    assert_eq!(expired.check(&Ids(1, 10, 100)), None);
+    assert_eq!("group".parse(), Ok(CooldownScope::Group));
+}
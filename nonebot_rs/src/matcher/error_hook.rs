@@ -0,0 +1,239 @@
+use crate::bot::Bot;
+use crate::event::Event;
+use crate::message::Message;
+use crate::NBError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Once, RwLock};
+use tracing::{event, Level};
+
+/// Handler 失败原因
+#[derive(Debug, Clone)]
+pub enum HandlerFailure {
+    /// Handler 返回错误
+    Error(NBError),
+    /// Handler panic
+    Panic {
+        message: String,
+        backtrace: Option<String>,
+    },
+}
+
+impl std::fmt::Display for HandlerFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandlerFailure::Error(e) => write!(f, "{}", e),
+            HandlerFailure::Panic { message, .. } => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+/// 错误上下文
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    /// 出错的 Matcher 名称
+    pub matcher_name: String,
+    /// 触发 Handler 的事件
+    pub event: Option<Event>,
+    pub failure: HandlerFailure,
+}
+
+impl ErrorContext {
+    /// 是否为非预期错误（API 失败、超时、存储失败、panic），文本类错误是面向用户的提示
+    pub fn is_unexpected(&self) -> bool {
+        match &self.failure {
+            HandlerFailure::Error(NBError::Api(_))
+            | HandlerFailure::Error(NBError::Timeout)
+            | HandlerFailure::Error(NBError::Store(_)) => true,
+            HandlerFailure::Error(_) => false,
+            HandlerFailure::Panic { .. } => true,
+        }
+    }
+}
+
+/// 自定义错误回调
+pub type ErrorCallback = Arc<dyn Fn(&ErrorContext) + Send + Sync>;
+
+#[derive(Clone)]
+struct ErrorHookConfig {
+    log: bool,
+    reply: Option<String>,
+    notify_superusers: bool,
+    callback: Option<ErrorCallback>,
+}
+
+/// Matchers 统一错误处理
+///
+/// - `NBError::Text` 与 `NBError::Other` 作为提示回复给用户，`NBError::State` 忽略
+/// - API 失败、超时与 panic 按配置记录日志（含 backtrace）、回复通用提示并通知 superuser
+///
+/// ```toml
+/// [matcher.error]
+/// log = true
+/// reply = "出了点问题，请稍后再试"
+/// notify_superusers = false
+/// ```
+#[derive(Clone)]
+pub struct ErrorHook {
+    config: Arc<RwLock<ErrorHookConfig>>,
+}
+
+impl std::fmt::Debug for ErrorHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let config = self.config.read().unwrap();
+        f.debug_struct("ErrorHook")
+            .field("log", &config.log)
+            .field("reply", &config.reply)
+            .field("notify_superusers", &config.notify_superusers)
+            .finish()
+    }
+}
+
+impl Default for ErrorHook {
+    fn default() -> Self {
+        ErrorHook {
+            config: Arc::new(RwLock::new(ErrorHookConfig {
+                log: true,
+                reply: None,
+                notify_superusers: false,
+                callback: None,
+            })),
+        }
+    }
+}
+
+impl ErrorHook {
+    /// 是否记录日志
+    pub fn set_log(&self, log: bool) {
+        self.config.write().unwrap().log = log;
+    }
+
+    /// 非预期错误时回复用户的通用提示，None 表示不回复
+    pub fn set_reply(&self, reply: Option<&str>) {
+        self.config.write().unwrap().reply = reply.map(|r| r.to_string());
+    }
+
+    /// 非预期错误时是否私聊通知 superuser
+    pub fn set_notify_superusers(&self, notify: bool) {
+        self.config.write().unwrap().notify_superusers = notify;
+    }
+
+    /// 设置自定义回调，所有错误都会调用
+    pub fn set_callback<F>(&self, callback: F)
+    where
+        F: Fn(&ErrorContext) + Send + Sync + 'static,
+    {
+        self.config.write().unwrap().callback = Some(Arc::new(callback));
+    }
+
+    pub(crate) fn load_config(&self, config: &HashMap<String, toml::Value>) {
+        if let Some(log) = config.get("log").and_then(|v| v.as_bool()) {
+            self.set_log(log);
+        }
+        if let Some(reply) = config.get("reply").and_then(|v| v.as_str()) {
+            self.set_reply(Some(reply).filter(|r| !r.is_empty()));
+        }
+        if let Some(notify) = config.get("notify_superusers").and_then(|v| v.as_bool()) {
+            self.set_notify_superusers(notify);
+        }
+    }
+
+    /// 处理 Handler 错误
+    pub async fn report(&self, context: ErrorContext, bot: Option<&Bot>) {
+        let config = self.config.read().unwrap().clone();
+        let unexpected = context.is_unexpected();
+        if config.log && unexpected {
+            match &context.failure {
+                HandlerFailure::Panic {
+                    message,
+                    backtrace: Some(backtrace),
+                } => event!(
+                    Level::ERROR,
+                    "Matcher {} panicked: {}\n{}",
+                    context.matcher_name,
+                    message,
+                    backtrace
+                ),
+                failure => event!(
+                    Level::ERROR,
+                    "Matcher {} failed: {}\n{}",
+                    context.matcher_name,
+                    failure,
+                    std::backtrace::Backtrace::capture()
+                ),
+            }
+        } else if config.log {
+            event!(
+                Level::DEBUG,
+                "Matcher {} returned: {}",
+                context.matcher_name,
+                context.failure
+            );
+        }
+        if let Some(callback) = &config.callback {
+            callback(&context);
+        }
+        let bot = match bot {
+            Some(bot) => bot,
+            None => return,
+        };
+        let reply = match &context.failure {
+            HandlerFailure::Error(NBError::Text(text)) => Some(vec![Message::text(text)]),
+            HandlerFailure::Error(NBError::Other(msg)) => Some(msg.clone()),
+            _ if unexpected => config.reply.as_ref().map(|r| vec![Message::text(r)]),
+            _ => None,
+        };
+        if let (Some(reply), Some(event)) = (reply, &context.event) {
+            reply_event(event, bot, reply).await;
+        }
+        if config.notify_superusers && unexpected {
+            let text = format!("Matcher {} 出错: {}", context.matcher_name, context.failure);
+            for superuser in &bot.config.superusers {
+                if let Ok(user_id) = superuser.parse::<i64>() {
+                    bot.send_private_msg_nrv(user_id, vec![Message::text(&text)])
+                        .await;
+                }
+            }
+        }
+    }
+}
+
+async fn reply_event(event: &Event, bot: &Bot, msg: crate::message::MessageChain) {
+    use crate::bot::Replyable;
+    match event {
+        Event::Message(e) => e.reply_by(bot, msg).await,
+        Event::Notice(e) => e.reply_by(bot, msg).await,
+        Event::Request(e) => e.reply_by(bot, msg).await,
+        Event::Meta(e) => e.reply_by(bot, msg).await,
+        Event::Nonebot(_) => {}
+    }
+}
+
+thread_local! {
+    static PANIC_BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static PANIC_HOOK: Once = Once::new();
+
+/// 安装记录 backtrace 的 panic hook，保留原有 hook
+///
+/// 与标准库一致，仅在设置 `RUST_BACKTRACE` / `RUST_LIB_BACKTRACE` 时记录
+pub(crate) fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let backtrace = std::backtrace::Backtrace::capture();
+            let backtrace = match backtrace.status() {
+                std::backtrace::BacktraceStatus::Captured => Some(backtrace.to_string()),
+                _ => None,
+            };
+            PANIC_BACKTRACE.with(|b| *b.borrow_mut() = backtrace);
+            previous(info);
+        }));
+    });
+}
+
+/// 取出当前线程最近一次 panic 的 backtrace
+pub(crate) fn take_panic_backtrace() -> Option<String> {
+    PANIC_BACKTRACE.with(|b| b.borrow_mut().take())
+}
@@ -0,0 +1,385 @@
+use super::Matcher;
+use crate::api_resp::{GroupInfo, GroupMemberInfo};
+use crate::bot::Bot;
+use crate::config::BotConfig;
+use crate::event::{
+    GroupId, GroupMessageEvent, MessageEvent, PrivateMessageEvent, Role, SelfId, UserId,
+};
+use crate::message::{Message, MessageChain};
+use crate::state::{BotState, GlobalState, GroupState, PluginState, ScopedState, UserState};
+use crate::store::PluginStore;
+use crate::NBResult;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// `#[event]` handler 参数提取时可用的上下文
+pub struct ExtractContext<'a, E>
+where
+    E: Clone,
+{
+    /// 当前事件
+    pub event: &'a E,
+    /// 当前 Matcher
+    pub matcher: &'a Matcher<E>,
+    /// handler 所在 module path
+    pub plugin: &'static str,
+    /// `#[event]` 中第一个 reg 表达式
+    pub regex: Option<&'static regex::Regex>,
+}
+
+impl<'a, E> ExtractContext<'a, E>
+where
+    E: Clone,
+{
+    pub fn new(
+        event: &'a E,
+        matcher: &'a Matcher<E>,
+        plugin: &'static str,
+        regex: Option<&'static regex::Regex>,
+    ) -> Self {
+        ExtractContext {
+            event,
+            matcher,
+            plugin,
+            regex,
+        }
+    }
+
+    /// Plugin 名称，handler 属于 `#[plugin]` 时为其名称，否则为 module path 的最后一段
+    pub fn plugin_name(&self) -> &'static str {
+        match crate::plugin::find_plugin(self.plugin) {
+            Some(plugin) => plugin.name,
+            None => self.plugin.rsplit("::").next().unwrap_or(self.plugin),
+        }
+    }
+
+    /// 当前 Bot，Matcher 未绑定 Bot 时返回错误
+    pub fn bot(&self) -> NBResult<&'a Bot> {
+        self.matcher
+            .bot
+            .as_ref()
+            .ok_or_else(|| "Matcher 未绑定 Bot".into())
+    }
+}
+
+/// 可从事件中提取的 `#[event]` handler 参数
+///
+/// 提取失败时 handler 不会运行，错误交由 Matchers 的错误处理统一上报，
+/// 参数声明为 `Option<T>` 时提取失败得到 `None`
+///
+/// ```rust,ignore
+/// #[event(bot_command = "/weather {city}")]
+/// async fn weather(
+///     event: MessageEvent,
+///     matcher: &mut Matcher<MessageEvent>,
+///     city: String,
+///     config: PluginConfig<WeatherConfig>,
+///     role: Option<Role>,
+/// ) -> NBResult<()> {
+///     ...
+/// }
+/// ```
+#[async_trait]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` 不能作为 `{E}` handler 的参数",
+    label = "未实现 FromEvent<{E}> 的参数",
+    note = "handler 参数需为事件、匹配器、bot_command 中的参数或实现 FromEvent 的类型"
+)]
+pub trait FromEvent<E>: Sized
+where
+    E: Clone + Send + Sync,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self>;
+}
+
+#[async_trait]
+impl<E, T> FromEvent<E> for Option<T>
+where
+    E: Clone + Send + Sync + 'static,
+    T: FromEvent<E>,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        Ok(T::from_event(ctx).await.ok())
+    }
+}
+
+#[async_trait]
+impl<E> FromEvent<E> for Bot
+where
+    E: Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        ctx.bot().cloned()
+    }
+}
+
+#[async_trait]
+impl<E> FromEvent<E> for BotConfig
+where
+    E: Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        ctx.bot().map(|bot| bot.config.clone())
+    }
+}
+
+/// Plugin 设置，读取 Nonebotrs.toml 中的 `[plugin.<Plugin 名称>]`
+///
+/// ```toml
+/// [plugin.weather]
+/// api_key = "key"
+/// ```
+#[derive(Debug, Clone)]
+pub struct PluginConfig<T>(pub T);
+
+impl<T> std::ops::Deref for PluginConfig<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<E, T> FromEvent<E> for PluginConfig<T>
+where
+    E: Clone + Send + Sync + 'static,
+    T: DeserializeOwned,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        let key = format!("plugin.{}", ctx.plugin_name());
+        crate::config::loaded_config()
+            .and_then(|config| config.get_config::<T>(&key))
+            .map(PluginConfig)
+            .ok_or_else(|| format!("缺少或无法解析设置 [{}]", key).into())
+    }
+}
+
+#[async_trait]
+impl<E> FromEvent<E> for PluginStore
+where
+    E: Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        Ok(crate::store::store(ctx.plugin_name()))
+    }
+}
+
+#[async_trait]
+impl<E> FromEvent<E> for GroupInfo
+where
+    E: GroupId + Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        let group_id = ctx.event.get_group_id();
+        if group_id == 0 {
+            return Err("非群聊事件".into());
+        }
+        ctx.bot()?
+            .get_group_info(group_id, false)
+            .await
+            .ok_or_else(|| format!("获取群 {} 信息失败", group_id).into())
+    }
+}
+
+#[async_trait]
+impl<E> FromEvent<E> for GroupMemberInfo
+where
+    E: GroupId + UserId + Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        let group_id = ctx.event.get_group_id();
+        if group_id == 0 {
+            return Err("非群聊事件".into());
+        }
+        let user_id = ctx.event.get_user_id();
+        ctx.bot()?
+            .get_group_member_info(group_id, user_id, false)
+            .await
+            .ok_or_else(|| format!("获取群 {} 成员 {} 信息失败", group_id, user_id).into())
+    }
+}
+
+macro_rules! scoped_state_extractor {
+    ($($name:ident),*) => {
+        $(
+            #[async_trait]
+            impl<E, T> FromEvent<E> for $name<T>
+            where
+                E: SelfId + UserId + GroupId + Clone + Send + Sync + 'static,
+                T: Default + Send + 'static,
+            {
+                async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+                    <$name<T> as ScopedState>::from_event(ctx.event, ctx.plugin_name())
+                }
+            }
+        )*
+    };
+}
+
+scoped_state_extractor!(GlobalState, PluginState, BotState, GroupState, UserState);
+
+/// 消息类事件的消息内容
+pub trait MessageContent {
+    /// 数组格式消息
+    fn message(&self) -> &MessageChain;
+    /// 字符串格式消息
+    fn raw_message(&self) -> &str;
+    /// 群聊发送者角色，私聊为 None
+    fn sender_role(&self) -> Option<&Role>;
+}
+
+impl MessageContent for MessageEvent {
+    fn message(&self) -> &MessageChain {
+        match self {
+            MessageEvent::Private(p) => p.message(),
+            MessageEvent::Group(g) => g.message(),
+        }
+    }
+
+    fn raw_message(&self) -> &str {
+        self.get_raw_message()
+    }
+
+    fn sender_role(&self) -> Option<&Role> {
+        match self {
+            MessageEvent::Private(p) => p.sender_role(),
+            MessageEvent::Group(g) => g.sender_role(),
+        }
+    }
+}
+
+impl MessageContent for PrivateMessageEvent {
+    fn message(&self) -> &MessageChain {
+        &self.message
+    }
+
+    fn raw_message(&self) -> &str {
+        &self.raw_message
+    }
+
+    fn sender_role(&self) -> Option<&Role> {
+        None
+    }
+}
+
+impl MessageContent for GroupMessageEvent {
+    fn message(&self) -> &MessageChain {
+        &self.message
+    }
+
+    fn raw_message(&self) -> &str {
+        &self.raw_message
+    }
+
+    fn sender_role(&self) -> Option<&Role> {
+        Some(&self.sender.role)
+    }
+}
+
+/// 群聊发送者角色，私聊消息提取失败
+#[async_trait]
+impl<E> FromEvent<E> for Role
+where
+    E: MessageContent + Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        ctx.event
+            .sender_role()
+            .cloned()
+            .ok_or_else(|| "私聊消息没有群角色".into())
+    }
+}
+
+/// 消息中所有文本段拼接而成的纯文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlainText(pub String);
+
+#[async_trait]
+impl<E> FromEvent<E> for PlainText
+where
+    E: MessageContent + Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        let text = ctx
+            .event
+            .message()
+            .iter()
+            .filter_map(|message| match message {
+                Message::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<String>();
+        Ok(PlainText(text))
+    }
+}
+
+/// 消息中的图片，优先取 url，缺省时为文件名
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Images(pub Vec<String>);
+
+#[async_trait]
+impl<E> FromEvent<E> for Images
+where
+    E: MessageContent + Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        let images = ctx
+            .event
+            .message()
+            .iter()
+            .filter_map(|message| match message {
+                Message::Image { file, url, .. } => Some(url.clone().unwrap_or_else(|| file.clone())),
+                _ => None,
+            })
+            .collect();
+        Ok(Images(images))
+    }
+}
+
+/// `#[event]` 中第一个 reg 表达式在消息上的捕获组
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegexCaptures {
+    /// 按序号的捕获组，0 为整体匹配
+    pub groups: Vec<Option<String>>,
+    /// 命名捕获组
+    pub named: HashMap<String, String>,
+}
+
+impl RegexCaptures {
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.groups.get(index).and_then(|group| group.as_deref())
+    }
+
+    pub fn name(&self, name: &str) -> Option<&str> {
+        self.named.get(name).map(|group| group.as_str())
+    }
+}
+
+#[async_trait]
+impl<E> FromEvent<E> for RegexCaptures
+where
+    E: MessageContent + Clone + Send + Sync + 'static,
+{
+    async fn from_event(ctx: &ExtractContext<'_, E>) -> NBResult<Self> {
+        let regex = ctx.regex.ok_or("#[event] 中未设置 reg 表达式")?;
+        let captures = regex
+            .captures(ctx.event.raw_message())
+            .ok_or("消息与 reg 表达式不匹配")?;
+        let groups = captures
+            .iter()
+            .map(|group| group.map(|group| group.as_str().to_string()))
+            .collect();
+        let named = regex
+            .capture_names()
+            .flatten()
+            .filter_map(|name| {
+                captures
+                    .name(name)
+                    .map(|group| (name.to_string(), group.as_str().to_string()))
+            })
+            .collect();
+        Ok(RegexCaptures { groups, named })
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// 未设置 `max_queue` 时的排队上限
+pub const DEFAULT_MAX_QUEUE: usize = 256;
+
+/// 超出并发上限时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// 排队等待，排队数超过上限时拒绝
+    Queue(usize),
+    /// 直接拒绝
+    Reject,
+}
+
+impl std::str::FromStr for Overflow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queue" => Ok(Overflow::Queue(DEFAULT_MAX_QUEUE)),
+            "reject" => Ok(Overflow::Reject),
+            _ => Err(format!("unknown overflow: {}", s)),
+        }
+    }
+}
+
+/// Handler 并发上限
+#[derive(Clone)]
+pub struct ConcurrencyLimit {
+    /// 最大同时运行数
+    pub max: usize,
+    pub overflow: Overflow,
+    semaphore: Arc<Semaphore>,
+    /// 运行中与排队中的 Handler 数
+    pending: Arc<AtomicUsize>,
+}
+
+impl std::fmt::Debug for ConcurrencyLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConcurrencyLimit")
+            .field("max", &self.max)
+            .field("overflow", &self.overflow)
+            .field("available", &self.semaphore.available_permits())
+            .field("pending", &self.pending.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl ConcurrencyLimit {
+    pub fn new(max: usize, overflow: Overflow) -> Self {
+        let max = max.max(1);
+        ConcurrencyLimit {
+            max,
+            overflow,
+            semaphore: Arc::new(Semaphore::new(max)),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// 占用运行或排队名额，已满时返回 None
+    ///
+    /// 在 spawn Handler 任务前调用，被拒绝的事件不会创建任务
+    pub fn reserve(&self) -> Option<Ticket> {
+        let capacity = match self.overflow {
+            Overflow::Reject => self.max,
+            Overflow::Queue(max_queue) => self.max.saturating_add(max_queue),
+        };
+        if self.pending.fetch_add(1, Ordering::SeqCst) >= capacity {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(Ticket {
+            semaphore: self.semaphore.clone(),
+            pending: self.pending.clone(),
+        })
+    }
+
+    /// 获取运行许可，被拒绝时返回 None
+    pub async fn acquire(&self) -> Option<Permit> {
+        self.reserve()?.acquire().await
+    }
+}
+
+/// 已占用的名额，drop 时归还
+#[derive(Debug)]
+pub struct Ticket {
+    semaphore: Arc<Semaphore>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl Ticket {
+    /// 等待运行许可
+    pub async fn acquire(self) -> Option<Permit> {
+        let permit = self.semaphore.clone().acquire_owned().await.ok()?;
+        Some(Permit {
+            _permit: permit,
+            _ticket: self,
+        })
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        self.pending.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 运行许可，Handler 结束后 drop
+#[derive(Debug)]
+pub struct Permit {
+    _permit: OwnedSemaphorePermit,
+    _ticket: Ticket,
+}
+
+#[derive(Debug, Default)]
+struct LimitsConfig {
+    concurrency: Option<ConcurrencyLimit>,
+    handle_timeout: Option<u64>,
+}
+
+/// Matchers 全局 Handler 限制，对所有 Matcher 生效
+///
+/// ```toml
+/// [matcher.limit]
+/// max_concurrency = 64   # 全局最大同时运行 Handler 数
+/// overflow = "queue"     # queue 或 reject
+/// max_queue = 256        # 排队上限，缺省为 256
+/// handle_timeout = 60    # Handler 默认最长运行秒数
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Limits {
+    config: Arc<RwLock<LimitsConfig>>,
+}
+
+impl Limits {
+    /// 设置全局并发上限，None 表示不限制
+    pub fn set_concurrency(&self, limit: Option<ConcurrencyLimit>) {
+        self.config.write().unwrap().concurrency = limit;
+    }
+
+    /// 设置 Handler 默认最长运行秒数，None 表示不限制
+    pub fn set_handle_timeout(&self, seconds: Option<u64>) {
+        self.config.write().unwrap().handle_timeout = seconds;
+    }
+
+    pub fn concurrency(&self) -> Option<ConcurrencyLimit> {
+        self.config.read().unwrap().concurrency.clone()
+    }
+
+    pub fn handle_timeout(&self) -> Option<u64> {
+        self.config.read().unwrap().handle_timeout
+    }
+
+    pub(crate) fn load_config(&self, config: &HashMap<String, toml::Value>) {
+        if let Some(max) = config.get("max_concurrency").and_then(|v| v.as_integer()) {
+            let overflow = match config.get("overflow").and_then(|v| v.as_str()) {
+                Some("reject") => Overflow::Reject,
+                _ => Overflow::Queue(
+                    config
+                        .get("max_queue")
+                        .and_then(|v| v.as_integer())
+                        .map(|q| q.max(0) as usize)
+                        .unwrap_or(DEFAULT_MAX_QUEUE),
+                ),
+            };
+            self.set_concurrency(Some(ConcurrencyLimit::new(max.max(1) as usize, overflow)));
+        }
+        if let Some(timeout) = config.get("handle_timeout").and_then(|v| v.as_integer()) {
+            self.set_handle_timeout(Some(timeout.max(0) as u64).filter(|t| *t > 0));
+        }
+    }
+}
+
+#[tokio::test]
+async fn limit_test() {
+    let limit = ConcurrencyLimit::new(1, Overflow::Queue(1));
+    let permit = limit.acquire().await.unwrap();
+    let queued = limit.reserve().unwrap();
+    // 运行与排队名额均已占满
+    assert!(limit.reserve().is_none());
+    let waiting = tokio::spawn(queued.acquire());
+    tokio::task::yield_now().await;
+    assert!(!waiting.is_finished());
+    drop(permit);
+    let permit = waiting.await.unwrap().unwrap();
+    assert!(limit.reserve().is_some());
+    drop(permit);
+
+    let limit = ConcurrencyLimit::new(1, Overflow::Reject);
+    let permit = limit.reserve().unwrap();
+    assert!(limit.reserve().is_none());
+    drop(permit);
+    assert!(limit.acquire().await.is_some());
+    assert_eq!("queue".parse(), Ok(Overflow::Queue(DEFAULT_MAX_QUEUE)));
+
+    let limits = Limits::default();
+    let config: HashMap<String, toml::Value> =
+        toml::from_str("max_concurrency = 2\nhandle_timeout = 0").unwrap();
+    limits.load_config(&config);
+    let concurrency = limits.concurrency().unwrap();
+    assert_eq!((concurrency.max, concurrency.overflow), (2, Overflow::Queue(DEFAULT_MAX_QUEUE)));
+    assert_eq!(limits.handle_timeout(), None);
+    let config: HashMap<String, toml::Value> =
+        toml::from_str("max_concurrency = 2\noverflow = \"reject\"\nhandle_timeout = 30").unwrap();
+    limits.load_config(&config);
+    assert_eq!(limits.concurrency().unwrap().overflow, Overflow::Reject);
+    assert_eq!(limits.handle_timeout(), Some(30));
+}
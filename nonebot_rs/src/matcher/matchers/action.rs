@@ -1,10 +1,13 @@
-use super::{Matchers, MatchersBTreeMap, MatchersHashMap};
+use super::{Matchers, MatchersBTreeMap, MatchersHashMap, MatchersInfo};
+use crate::matcher::{
+    AccessControl, ErrorHook, Limits, MatcherSwitches, Middleware, Middlewares, SessionRegistry,
+};
 use crate::event::{MessageEvent, MetaEvent, NoticeEvent, RequestEvent};
 use crate::matcher::{action, Matcher};
 
 
 use std::collections::{BTreeMap, HashMap};
-use tokio::sync::broadcast;
+use std::sync::Arc;
 
 impl Matchers {
     /// 新建 Matchers
@@ -14,15 +17,23 @@ impl Matchers {
         request: Option<MatchersBTreeMap<RequestEvent>>,
         meta: Option<MatchersBTreeMap<MetaEvent>>,
     ) -> Matchers {
-        let (sender, _) = broadcast::channel(32);
+        let (sender, receiver) = action::channel();
         Matchers {
-            message: unoptionb(&message),
-            notice: unoptionb(&notice),
-            request: unoptionb(&request),
-            meta: unoptionb(&meta),
+            message: Arc::new(unoptionb(&message)),
+            notice: Arc::new(unoptionb(&notice)),
+            request: Arc::new(unoptionb(&request)),
+            meta: Arc::new(unoptionb(&meta)),
             bot_getter: None,
             action_sender: sender,
+            action_receiver: Arc::new(std::sync::Mutex::new(Some(receiver))),
             config: HashMap::new(),
+            infos: MatchersInfo::default(),
+            sessions: SessionRegistry::default(),
+            switches: MatcherSwitches::default(),
+            access: AccessControl::default(),
+            middlewares: Middlewares::default(),
+            error_hook: ErrorHook::default(),
+            limits: Limits::default(),
         }
     }
 
@@ -91,15 +102,73 @@ impl Matchers {
         f(&self.meta, &self.config).await;
     }
 
+    /// 获取所有已注册 Matcher 的概要信息（不含临时 Matcher）
+    pub fn get_infos(&self) -> MatchersInfo {
+        self.infos.clone()
+    }
+
+    /// 向中间件链尾部添加中间件
+    pub fn add_middleware<M>(&mut self, middleware: M) -> &mut Self
+    where
+        M: Middleware + 'static,
+    {
+        self.middlewares.add(middleware);
+        self
+    }
+
+    /// 获取中间件链，可交由其他 Plugin 在运行期间注册中间件
+    pub fn get_middlewares(&self) -> Middlewares {
+        self.middlewares.clone()
+    }
+
+    /// 获取全局 Handler 限制
+    pub fn get_limits(&self) -> Limits {
+        self.limits.clone()
+    }
+
+    /// 获取错误处理，可设置自定义回调
+    pub fn get_error_hook(&self) -> ErrorHook {
+        self.error_hook.clone()
+    }
+
+    /// 获取 Matcher 运行期开关
+    pub fn get_switches(&self) -> MatcherSwitches {
+        self.switches.clone()
+    }
+
+    /// 获取黑白名单
+    pub fn get_access(&self) -> AccessControl {
+        self.access.clone()
+    }
+
+    /// 获取会话注册表
+    pub fn get_sessions(&self) -> SessionRegistry {
+        self.sessions.clone()
+    }
+
     #[doc(hidden)]
     fn add_matcher<E>(
-        matcherb: &mut MatchersBTreeMap<E>,
+        matcherb: &mut Arc<MatchersBTreeMap<E>>,
         mut matcher: Matcher<E>,
-        action_sender: broadcast::Sender<action::MatchersAction>,
+        action_sender: super::ActionSender,
+        infos: &MatchersInfo,
+        sessions: &SessionRegistry,
+        switches: &MatcherSwitches,
+        error_hook: &ErrorHook,
     ) where
         E: Clone,
     {
         matcher.set_action_sender(action_sender);
+        matcher.set_sessions(sessions.clone());
+        matcher.set_switches(switches.clone());
+        matcher.set_error_hook(error_hook.clone());
+        if !matcher.is_temp() {
+            infos
+                .write()
+                .unwrap()
+                .insert(matcher.name.clone(), matcher.info());
+        }
+        let matcherb = Arc::make_mut(matcherb);
         match matcherb.get_mut(&matcher.priority) {
             Some(h) => {
                 h.insert(matcher.name.clone(), matcher);
@@ -114,7 +183,15 @@ impl Matchers {
 
     /// 向 Matchers 添加 Matcher<MessageEvent>
     pub fn add_message_matcher(&mut self, matcher: Matcher<MessageEvent>) -> &mut Self {
-        Matchers::add_matcher(&mut self.message, matcher, self.action_sender.clone());
+        Matchers::add_matcher(
+            &mut self.message,
+            matcher,
+            self.action_sender.clone(),
+            &self.infos,
+            &self.sessions,
+            &self.switches,
+            &self.error_hook,
+        );
         self
     }
 
@@ -128,7 +205,15 @@ impl Matchers {
 
     /// 向 Matchers 添加 Matcher<NoticeEvent>
     pub fn add_notice_matcher(&mut self, matcher: Matcher<NoticeEvent>) -> &mut Self {
-        Matchers::add_matcher(&mut self.notice, matcher, self.action_sender.clone());
+        Matchers::add_matcher(
+            &mut self.notice,
+            matcher,
+            self.action_sender.clone(),
+            &self.infos,
+            &self.sessions,
+            &self.switches,
+            &self.error_hook,
+        );
         self
     }
     /// 向 Matchers 添加 Vec<Matcher<NoticeEvent>>
@@ -141,7 +226,15 @@ impl Matchers {
 
     /// 向 Matchers 添加 Matcher<RequestEvent>
     pub fn add_request_matcher(&mut self, matcher: Matcher<RequestEvent>) -> &mut Self {
-        Matchers::add_matcher(&mut self.request, matcher, self.action_sender.clone());
+        Matchers::add_matcher(
+            &mut self.request,
+            matcher,
+            self.action_sender.clone(),
+            &self.infos,
+            &self.sessions,
+            &self.switches,
+            &self.error_hook,
+        );
         self
     }
     /// 向 Matchers 添加 Vec<Matcher<RequestEvent>>
@@ -154,46 +247,80 @@ impl Matchers {
 
     /// 向 Matchers 添加 Matcher<MetaEvent>
     pub fn add_meta_matcher(&mut self, matcher: Matcher<MetaEvent>) -> &mut Self {
-        Matchers::add_matcher(&mut self.meta, matcher, self.action_sender.clone());
+        Matchers::add_matcher(
+            &mut self.meta,
+            matcher,
+            self.action_sender.clone(),
+            &self.infos,
+            &self.sessions,
+            &self.switches,
+            &self.error_hook,
+        );
         self
     }
 
-    /// 根据 Matcher.name 从 Matchers 移除 Matcher
-    pub fn remove_matcher(&mut self, name: &str) {
-        fn remove_matcher_<E>(matcherb: &mut MatchersBTreeMap<E>, name: &str)
+    /// 向 Matchers 添加任意支持的 Matcher 或 Vec<Matcher>
+    pub fn add<M: AddToMatchers>(&mut self, matcher: M) -> &mut Self {
+        matcher.add_to(self);
+        self
+    }
+
+    /// 根据 Matcher.name 从 Matchers 移除 Matcher，返回 Matcher 是否存在
+    pub fn remove_matcher(&mut self, name: &str) -> bool {
+        fn remove_matcher_<E>(matcherb: &mut Arc<MatchersBTreeMap<E>>, name: &str) -> bool
         where
             E: Clone,
         {
-            for (_, matcherh) in matcherb.iter_mut() {
+            // 不存在时不复制快照
+            if !matcherb.values().any(|matcherh| matcherh.contains_key(name)) {
+                return false;
+            }
+            for (_, matcherh) in Arc::make_mut(matcherb).iter_mut() {
                 if let Some(_) = matcherh.remove(name) {
-                    return;
+                    return true;
                 }
             }
+            false
         }
 
-        remove_matcher_(&mut self.message, name);
-        remove_matcher_(&mut self.notice, name);
-        remove_matcher_(&mut self.request, name);
-        remove_matcher_(&mut self.meta, name);
+        let removed = remove_matcher_(&mut self.message, name)
+            | remove_matcher_(&mut self.notice, name)
+            | remove_matcher_(&mut self.request, name)
+            | remove_matcher_(&mut self.meta, name);
+        self.infos.write().unwrap().remove(name);
+        self.sessions.release_matcher(name);
+        removed
     }
 
-    /// 根据 Matcher.name disable Matcher
-    pub fn disable_matcher(&mut self, name: &str, disable: bool) {
-        fn disable_matcher_<E>(matcherb: &mut MatchersBTreeMap<E>, name: &str, disable: bool)
+    /// 根据 Matcher.name disable Matcher，返回 Matcher 是否存在
+    pub fn disable_matcher(&mut self, name: &str, disable: bool) -> bool {
+        fn disable_matcher_<E>(
+            matcherb: &mut Arc<MatchersBTreeMap<E>>,
+            name: &str,
+            disable: bool,
+        ) -> bool
         where
             E: Clone,
         {
-            for (_, matcherh) in matcherb.iter_mut() {
+            if !matcherb.values().any(|matcherh| matcherh.contains_key(name)) {
+                return false;
+            }
+            for (_, matcherh) in Arc::make_mut(matcherb).iter_mut() {
                 if let Some(matcher) = matcherh.get_mut(name) {
                     matcher.set_disable(disable);
                 }
             }
+            true
         }
 
-        disable_matcher_(&mut self.message, name, disable);
-        disable_matcher_(&mut self.notice, name, disable);
-        disable_matcher_(&mut self.request, name, disable);
-        disable_matcher_(&mut self.meta, name, disable);
+        let found = disable_matcher_(&mut self.message, name, disable)
+            | disable_matcher_(&mut self.notice, name, disable)
+            | disable_matcher_(&mut self.request, name, disable)
+            | disable_matcher_(&mut self.meta, name, disable);
+        if let Some(info) = self.infos.write().unwrap().get_mut(name) {
+            info.disable = disable;
+        }
+        found
     }
 }
 
@@ -208,3 +335,39 @@ where
         None => BTreeMap::new(),
     }
 }
+
+/// 可添加到 Matchers 的 Matcher
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` 不能添加到 Matchers",
+    note = "仅支持 MessageEvent/NoticeEvent/RequestEvent/MetaEvent 的 Matcher 及其 Vec"
+)]
+pub trait AddToMatchers {
+    fn add_to(self, matchers: &mut Matchers);
+}
+
+macro_rules! add_to_matchers {
+    ($($e:ty => $add:ident),*) => {
+        $(
+            impl AddToMatchers for Matcher<$e> {
+                fn add_to(self, matchers: &mut Matchers) {
+                    matchers.$add(self);
+                }
+            }
+        )*
+    };
+}
+
+add_to_matchers!(
+    MessageEvent => add_message_matcher,
+    NoticeEvent => add_notice_matcher,
+    RequestEvent => add_request_matcher,
+    MetaEvent => add_meta_matcher
+);
+
+impl<M: AddToMatchers> AddToMatchers for Vec<M> {
+    fn add_to(self, matchers: &mut Matchers) {
+        for matcher in self {
+            matcher.add_to(matchers);
+        }
+    }
+}
@@ -1,113 +1,185 @@
-use crate::event::{Event, MessageEvent, MetaEvent, NoticeEvent, RequestEvent, SelfId};
-use crate::matcher::Matcher;
+use crate::bot::Replyable;
+use crate::event::{Event, GroupId, MessageEvent, MetaEvent, NoticeEvent, RequestEvent, SelfId, UserId};
+use crate::matcher::{
+    AccessControl, ErrorHook, Limits, Matcher, MatcherInfo, MatcherSwitches, Middlewares, SessionKey,
+    SessionRegistry,
+};
 use async_trait::async_trait;
 use colored::*;
 use std::collections::HashMap;
 use std::collections::btree_map::BTreeMap;
+use std::sync::{Arc, RwLock};
 use tokio::sync::broadcast;
 use tracing::{event, Level};
 
 mod action;
 
+pub use action::AddToMatchers;
+
 /// 按 `priority` 依序存储 `MatchersHashMap`
 pub type MatchersBTreeMap<E> = BTreeMap<i8, MatchersHashMap<E>>;
 /// 使用唯一名字存储 `Matcher`
 pub type MatchersHashMap<E> = HashMap<String, Matcher<E>>;
 /// Matchers Action Sender
-pub type ActionSender = broadcast::Sender<super::action::MatchersAction>;
+pub type ActionSender = super::action::MatchersActionSender;
+/// 所有已注册 Matcher 的概要信息，以 Matcher name 为键
+pub type MatchersInfo = Arc<RwLock<HashMap<String, MatcherInfo>>>;
 
 pub const PLUGIN_NAME: &'static str = "Matcher";
 
 /// 根据 `Event` 类型分类存储对应的 `Matcher`
+///
+/// 各类 Matcher 以 `Arc` 快照存储，分发事件时仅复制 `Arc`，增删改时写时复制后替换快照
 #[derive(Clone, Debug)]
 pub struct Matchers {
     /// MessageEvent 对应 MatcherBTreeMap
-    pub message: MatchersBTreeMap<MessageEvent>,
+    pub message: Arc<MatchersBTreeMap<MessageEvent>>,
     /// NoticeEvent 对应 MatcherBTreeMap
-    pub notice: MatchersBTreeMap<NoticeEvent>,
+    pub notice: Arc<MatchersBTreeMap<NoticeEvent>>,
     /// RequestEvent 对应 MatcherBTreeMap
-    pub request: MatchersBTreeMap<RequestEvent>,
+    pub request: Arc<MatchersBTreeMap<RequestEvent>>,
     /// MetaEvent 对应 MatcherBTreeMap
-    pub meta: MatchersBTreeMap<MetaEvent>,
+    pub meta: Arc<MatchersBTreeMap<MetaEvent>>,
     /// Bot Watch channel Receiver
     bot_getter: Option<crate::BotGetter>,
     /// Matchers Action Sender
     action_sender: ActionSender,
+    /// Matchers Action Receiver，运行时取出
+    action_receiver: Arc<std::sync::Mutex<Option<super::action::MatchersActionReceiver>>>,
     /// Config
     config: HashMap<String, HashMap<String, toml::Value>>,
+    /// Matcher 概要信息
+    infos: MatchersInfo,
+    /// 会话注册表
+    sessions: SessionRegistry,
+    /// 运行期开关
+    switches: MatcherSwitches,
+    /// 黑白名单
+    access: AccessControl,
+    /// 中间件链
+    middlewares: Middlewares,
+    /// 错误处理
+    error_hook: ErrorHook,
+    /// 全局 Handler 限制
+    limits: Limits,
 }
 
 impl Matchers {
-    async fn handle_events(&mut self, event: Event, bot: &crate::bot::Bot) {
+    /// 经过中间件链分发单个事件
+    ///
+    /// 通常由 Matchers Plugin 接收事件后调用
+    pub async fn dispatch(&mut self, mut event: Event, bot: &crate::bot::Bot) {
+        if let Event::Nonebot(_) = event {
+            self.handle_events(event, bot).await;
+            return;
+        }
+        if !self.access.is_event_allowed(&event, &bot.config) {
+            event!(Level::DEBUG, "Event ignored by access lists");
+            return;
+        }
+        if self.middlewares.is_empty() {
+            self.handle_events(event, bot).await;
+            return;
+        }
+        if !self.middlewares.before_match(&mut event, bot).await {
+            return;
+        }
+        let matched = self.handle_events(event.clone(), bot).await;
+        self.middlewares.after_dispatch(&event, bot, &matched).await;
+    }
+
+    /// 按事件类型分发，返回匹配成功的 Matcher 名称
+    async fn handle_events(&mut self, event: Event, bot: &crate::bot::Bot) -> Vec<String> {
         match event {
             Event::Message(e) => {
-                self.handle_event(self.message.clone(), e, bot.clone())
-                    .await;
-            }
-            Event::Notice(e) => {
-                self.handle_event(self.notice.clone(), e, bot.clone()).await;
+                if let Some(name) = self.handle_session(&e, bot).await {
+                    return vec![name];
+                }
+                self.handle_event(self.message.clone(), e, bot)
+                    .await
             }
+            Event::Notice(e) => self.handle_event(self.notice.clone(), e, bot).await,
             Event::Request(e) => {
-                self.handle_event(self.request.clone(), e, bot.clone())
-                    .await;
-            }
-            Event::Meta(e) => {
-                self.handle_event(self.meta.clone(), e, bot.clone()).await;
+                self.handle_event(self.request.clone(), e, bot)
+                    .await
             }
-            Event::Nonebot(e) => match e {
-                crate::event::NbEvent::BotConnect { bot } => {
-                    log_load_matchers(&self);
-                    self.run_on_connect(bot, false).await;
-                }
-                crate::event::NbEvent::BotDisconnect { bot } => {
-                    self.run_on_connect(bot, true).await;
+            Event::Meta(e) => self.handle_event(self.meta.clone(), e, bot).await,
+            Event::Nonebot(e) => {
+                match e {
+                    crate::event::NbEvent::BotConnect { bot } => {
+                        log_load_matchers(self);
+                        self.run_on_connect(bot, false).await;
+                    }
+                    crate::event::NbEvent::BotDisconnect { bot } => {
+                        self.run_on_connect(bot, true).await;
+                    }
                 }
-            },
+                vec![]
+            }
         }
     }
 
     /// 接收按类型分发后的 Event 逐级匹配 Matcher
     async fn handle_event<E>(
         &mut self,
-        mut matcherb: MatchersBTreeMap<E>,
+        matcherb: Arc<MatchersBTreeMap<E>>,
         event: E,
-        bot: crate::bot::Bot,
-    ) where
-        E: Clone + Send + 'static + std::fmt::Debug + SelfId,
+        bot: &crate::bot::Bot,
+    ) -> Vec<String>
+    where
+        E: Clone
+            + Send
+            + Sync
+            + 'static
+            + std::fmt::Debug
+            + SelfId
+            + UserId
+            + GroupId
+            + Replyable
+            + Into<Event>,
     {
         event!(Level::TRACE, "handling event {:?}", event);
+        let mut matched = vec![];
         // 根据不同 Event 类型，逐级匹配，判定是否 Block
-        for (_, matcherh) in matcherb.iter_mut() {
+        for (_, matcherh) in matcherb.iter() {
             if self
-                ._handler_event(matcherh, event.clone(), bot.clone())
+                ._handler_event(matcherh, &event, bot, &mut matched)
                 .await
             {
                 break;
             };
         }
+        matched
     }
 
     #[doc(hidden)]
     async fn _handler_event<E>(
         &mut self,
-        matcherh: &mut MatchersHashMap<E>,
-        e: E,
-        bot: crate::bot::Bot,
+        matcherh: &MatchersHashMap<E>,
+        e: &E,
+        bot: &crate::bot::Bot,
+        matched_names: &mut Vec<String>,
     ) -> bool
     where
-        E: Clone + Send + 'static + std::fmt::Debug + SelfId,
+        E: Clone
+            + Send
+            + Sync
+            + 'static
+            + std::fmt::Debug
+            + SelfId
+            + UserId
+            + GroupId
+            + Replyable
+            + Into<Event>,
     {
         event!(Level::TRACE, "handling event_ {:?}", e);
         // 每级 Matcher 匹配，返回是否 block
         let mut get_block = false;
-        let config = bot.config.clone();
-        for (name, matcher) in matcherh.iter_mut() {
-            let matched = matcher
-                .build(bot.clone())
-                .match_(e.clone(), config.clone(), self)
-                .await;
+        for (name, matcher) in matcherh.iter() {
+            let matched = matcher.match_(e, bot, self).await;
             if matched {
                 event!(Level::INFO, "Matched {}", name.blue());
+                matched_names.push(name.clone());
                 if matcher.is_block() {
                     get_block = true;
                 }
@@ -120,18 +192,80 @@ impl Matchers {
         get_block
     }
 
-    async fn event_recv(mut self, mut event_receiver: crate::EventReceiver) {
-        let mut receiver = self.action_sender.subscribe();
-        while let Ok(event) = event_receiver.recv().await {
-
-            match receiver.try_recv() {
-                Ok(action) => self.handle_action(action),
-                Err(_) => {}
+    /// 处于会话中的消息只交给占用会话的临时 Matcher，已被会话处理时返回该 Matcher 名称
+    async fn handle_session(
+        &mut self,
+        event: &MessageEvent,
+        bot: &crate::bot::Bot,
+    ) -> Option<String> {
+        let key = SessionKey::new(event, None);
+        let name = match self.sessions.get(&key) {
+            Some(name) => name,
+            None => return None,
+        };
+        if self.sessions.is_escape(event.get_raw_message()) {
+            event!(Level::INFO, "Session {} escaped", name.blue());
+            self.remove_matcher(&name);
+            if let Some(reply) = self.sessions.escape_reply() {
+                bot.send_by_message_event(event, vec![crate::message::Message::text(reply)])
+                    .await;
             }
+            return Some(name);
+        }
+        let matcher = self
+            .message
+            .values()
+            .find_map(|matcherh| matcherh.get(&name))
+            .cloned();
+        let matcher = match matcher {
+            Some(matcher) => matcher,
+            // 会话先于 Temp Matcher 占用，注册完成前按普通消息处理
+            None => return None,
+        };
+        let matched = matcher.match_(event, bot, self).await;
+        if matched {
+            event!(Level::INFO, "Session Matched {}", name.blue());
+            if matcher.is_temp() {
+                self.remove_matcher(&name);
+            }
+            return Some(name);
+        }
+        None
+    }
+
+    /// 处理所有已发送的 Action
+    fn drain_actions(&mut self, action_receiver: &mut super::action::MatchersActionReceiver) {
+        while let Ok(item) = action_receiver.try_recv() {
+            self.handle_action_item(item);
+        }
+    }
 
-            let bots = self.bot_getter.clone().unwrap().borrow().clone();
-            if let Some(bot) = bots.get(&event.get_self_id()) {
-                self.handle_events(event, bot).await;
+    async fn event_recv(mut self, mut event_receiver: crate::EventReceiver) {
+        let mut action_receiver = match self.action_receiver.lock().unwrap().take() {
+            Some(receiver) => receiver,
+            None => {
+                event!(Level::ERROR, "Matchers is already running");
+                return;
+            }
+        };
+        loop {
+            tokio::select! {
+                // 优先处理 Action，保证事件分发前已发送的 Action 均已生效
+                biased;
+                Some(item) = action_receiver.recv() => self.handle_action_item(item),
+                event = event_receiver.recv() => match event {
+                    Ok(event) => {
+                        self.drain_actions(&mut action_receiver);
+                        let bots = self.bot_getter.clone().unwrap().borrow().clone();
+                        if let Some(bot) = bots.get(&event.get_self_id()) {
+                            self.dispatch(event, bot).await;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(count)) => {
+                        event!(Level::WARN, "Matchers lagged, {} events skipped", count);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
             }
         }
     }
@@ -142,6 +276,9 @@ impl crate::Plugin for Matchers {
     fn run(&self, event_receiver: crate::EventReceiver, bot_getter: crate::BotGetter) {
         let mut m = self.clone();
         m.bot_getter = Some(bot_getter);
+        crate::matcher::error_hook::install_panic_hook();
+        m.switches.load();
+        m.access.load();
         tokio::spawn(m.event_recv(event_receiver));
     }
 
@@ -149,9 +286,41 @@ impl crate::Plugin for Matchers {
         PLUGIN_NAME
     }
 
+    #[cfg(feature = "matcher")]
+    fn register(&mut self, plugins: &[&'static crate::plugin::PluginDef]) {
+        for plugin in plugins {
+            plugin.register_matchers(self);
+        }
+    }
+
     async fn load_config(&mut self, config: toml::Value) {
         let config: HashMap<String, HashMap<String, toml::Value>> =
             config.try_into().expect("Matchers get error config");
+        if let Some(limit) = config.get("limit") {
+            self.limits.load_config(limit);
+        }
+        if let Some(path) = config
+            .get("switch")
+            .and_then(|switch| switch.get("path"))
+            .and_then(|v| v.as_str())
+        {
+            self.switches.set_path(path);
+        }
+        if let Some(access) = config.get("access") {
+            self.access.load_config(access);
+        }
+        if let Some(error) = config.get("error") {
+            self.error_hook.load_config(error);
+        }
+        if let Some(session) = config.get("session") {
+            if let Some(escapes) = session.get("escapes").and_then(|v| v.clone().try_into().ok()) {
+                self.sessions.set_escapes(escapes);
+            }
+            if let Some(reply) = session.get("escape_reply").and_then(|v| v.as_str()) {
+                self.sessions
+                    .set_escape_reply(Some(reply.to_string()).filter(|r| !r.is_empty()));
+            }
+        }
         self.config = config;
         self.load_all_matcher_config().await;
         event!(Level::INFO, "Loaded Matchers config: {:?}", self.config);
@@ -178,3 +347,64 @@ where
         }
     }
 }
+
+#[tokio::test]
+async fn handle_timeout_test() {
+    use crate::matcher::Handler;
+
+    struct Ask;
+
+    #[async_trait]
+    impl Handler<MessageEvent> for Ask {
+        fn match_(&self, event: &mut MessageEvent) -> bool {
+            event.get_raw_message() == "/ask"
+        }
+
+        async fn handle(&self, _: MessageEvent, matcher: &mut Matcher<MessageEvent>) {
+            matcher
+                .request_message_callback(30, None, None, |_, _, _| Box::pin(async { true }))
+                .await
+                .ok();
+        }
+    }
+
+    let (api_sender, _api_receiver) = tokio::sync::mpsc::channel(1);
+    let (action_sender, _action_receiver) = tokio::sync::mpsc::channel(1);
+    let (_resp_sender, resp_receiver) = tokio::sync::watch::channel(
+        serde_json::from_str(r#"{"status": "ok", "retcode": 0, "data": null, "echo": ""}"#)
+            .unwrap(),
+    );
+    let bot = crate::bot::Bot::new(
+        11,
+        crate::config::BotConfig::default(),
+        api_sender,
+        action_sender,
+        resp_receiver,
+    );
+    let event: Event = serde_json::from_str(
+        r#"{"post_type":"message","message_type":"private","time":1631193409,"self_id":11,"sub_type":"friend","message_id":1,"user_id":22,"message":[],"raw_message":"/ask","font":0,"sender":{"user_id":22,"nickname":"","sex":"unknown","age":0}}"#,
+    )
+    .unwrap();
+    let key = match &event {
+        Event::Message(e) => SessionKey::new(e, None),
+        _ => unreachable!(),
+    };
+
+    let mut matchers = Matchers::new_empty();
+    let mut action_receiver = matchers.action_receiver.lock().unwrap().take().unwrap();
+    matchers.add_message_matcher(Matcher::new("ask", Ask).set_handle_timeout(1));
+    let sessions = matchers.get_sessions();
+    matchers.dispatch(event, &bot).await;
+
+    // Handler 进入会话：占用会话并注册 Temp Matcher
+    let item = action_receiver.recv().await.unwrap();
+    matchers.handle_action_item(item);
+    let name = sessions.get(&key).unwrap();
+    assert!(matchers.message[&0].contains_key(&name));
+
+    // Handler 超时被取消后移除 Temp Matcher 并释放会话
+    let item = action_receiver.recv().await.unwrap();
+    matchers.handle_action_item(item);
+    assert!(!sessions.is_active(&key));
+    assert!(matchers.message.get(&0).map_or(true, |h| !h.contains_key(&name)));
+}
@@ -1,13 +1,13 @@
 use std::future::Future;
 use std::sync::Arc;
-use super::{build_temp_message_event_matcher, Handler, Matcher};
+use super::{build_temp_message_event_matcher, Handler, Matcher, TempMatcherGuard};
 use crate::event::{MessageEvent, NoticeEvent, NoticeSubType, NoticeType, Role, UserId};
 use crate::{ApiChannelItem, NBError, NBResult};
 use async_trait::async_trait;
 use colored::*;
 use futures_util::SinkExt;
 use tracing::{event, Level};
-use crate::matcher::{build_temp_notice_event_matcher, Session};
+use crate::matcher::{Session, SessionKey};
 use crate::message::MessageChain;
 use crate::utils::remove_space;
 
@@ -145,22 +145,29 @@ impl Matcher<MessageEvent> {
             // timeout 后调用，通知接受端 Timeout
             fn timeout_drop(&self, matcher: &Matcher<MessageEvent>) {
                 let sender = matcher.bot.clone().unwrap().api_sender;
-                tokio::spawn(async move { sender.send(ApiChannelItem::TimeOut).await.unwrap() });
+                tokio::spawn(async move {
+                    if sender.send(ApiChannelItem::TimeOut).await.is_err() {
+                        event!(Level::DEBUG, "Temp Matcher receiver dropped before timeout");
+                    }
+                });
             }
             
-            fn match_(&mut self, _: &mut MessageEvent) -> bool {
+            fn match_(&self, _: &mut MessageEvent) -> bool {
                 true
             }
             
             async fn handle(&self, event: MessageEvent, matcher: &mut Matcher<MessageEvent>) {
-                matcher
+                let sent = matcher
                     .bot
                     .clone()
                     .unwrap()
                     .api_sender
                     .send(ApiChannelItem::MessageEvent(event))
-                    .await
-                    .unwrap();
+                    .await;
+                // 等待方已结束（如 Handler 超时被取消）时忽略
+                if sent.is_err() {
+                    event!(Level::DEBUG, "Temp Matcher receiver dropped, event ignored");
+                }
             }
         }
 
@@ -179,7 +186,22 @@ impl Matcher<MessageEvent> {
         );
         // 绑定专用 Bot
         m.bot = Some(bot);
-        self.set_message_matcher(m).await;
+        let name = m.name.clone();
+        // 先占用会话再注册 Temp Matcher，同一会话同时只能有一个等待中的请求
+        let key = SessionKey::new(&event, user_id);
+        let mut guard = TempMatcherGuard::new(&name, self.action_sender.clone());
+        if let Some(sessions) = &self.sessions {
+            if !sessions.claim(key, &name) {
+                event!(Level::DEBUG, "Session already in conversation");
+                return Err(NBError::State(Session::On));
+            }
+            guard.set_session(sessions.clone(), key);
+        }
+        // 此后无论以何种方式返回或被取消，guard 均会移除 Temp Matcher 并释放自身占用的会话
+        if !self.set_message_matcher(m).await {
+            event!(Level::WARN, "Temp Matcher {} register failed", name);
+            return Err(NBError::State(Session::Error));
+        }
 
         // Temp Matcher 已就绪，发送提示信息
         if let Some(msg) = msg {
@@ -218,6 +240,10 @@ impl Matcher<MessageEvent> {
         }
         Err(NBError::State(Session::Stop))
     }
+    /// 请求 NoticeEvent
+    ///
+    /// 等待当前会话用户（或指定 user_id）的下一个符合 notice_type 与 sub_type 的 NoticeEvent，
+    /// 超时返回 None
     pub async fn request_notice_callback(
         &self,
         timeout: i64,
@@ -226,94 +252,31 @@ impl Matcher<MessageEvent> {
         sub_type: Option<NoticeSubType>,
         msg: Option<MessageChain>,
     ) -> Option<NoticeEvent> {
-        /// 临时 Matcher 的 Handler struct
-        struct TempNoticeMatcher;
-        
-        #[async_trait]
-        impl Handler<MessageEvent> for TempNoticeMatcher {
-            // timeout 后调用，通知接受端 Timeout
-            fn timeout_drop(&self, matcher: &Matcher<MessageEvent>) {
-                let sender = matcher.bot.clone().unwrap().api_sender;
-                tokio::spawn(async move { sender.send(ApiChannelItem::TimeOut).await.unwrap() });
-            }
-            
-            fn match_(&mut self, _: &mut MessageEvent) -> bool {
-                true
-            }
-            
-            async fn handle(&self, event: MessageEvent, matcher: &mut Matcher<MessageEvent>) {
-                matcher
-                    .bot
-                    .clone()
-                    .unwrap()
-                    .api_sender
-                    .send(ApiChannelItem::MessageEvent(event))
-                    .await
-                    .unwrap();
-            }
-        }
-        
-        // 搭建临时通道接受 MessageEvent
-        let (sender, mut receiver) = tokio::sync::mpsc::channel::<ApiChannelItem>(4);
-        let event = self.event.clone().unwrap();
-        // 根据提供的 event Handler 构建仅指向当先通话的 Temp Matcher
-        let mut m = build_temp_message_event_matcher(user_id, timeout, &event, TempNoticeMatcher);
-        // 使用临时通道构建专用 Bot
-        let bot = crate::bot::Bot::new(
-            0,
-            crate::config::BotConfig::default(),
-            sender,
-            self.bot.clone().unwrap().action_sender.clone(),
-            self.bot.clone().unwrap().api_resp_watcher.clone(),
-        );
-        // 绑定专用 Bot
-        m.bot = Some(bot);
-        self.set_message_matcher(m).await;
-        
-        // Temp Matcher 已就绪，发送提示信息
+        let user_id = match (user_id, &self.event) {
+            (Some(user_id), _) => user_id,
+            (None, Some(event)) => event.get_user_id(),
+            (None, None) => return None,
+        };
+        let filter = move |event: &NoticeEvent| {
+            // 未设置的类型不参与过滤
+            event.get_user_id() == user_id
+                && notice_type.iter().all(|n| &event.notice_type == n)
+                && sub_type.iter().all(|s| event.sub_type.as_ref() == Some(s))
+        };
+        let wait = self.wait_for(filter, timeout);
+
+        // 等待已注册，发送提示信息
         if let Some(msg) = msg {
             self.send_(msg).await;
         }
-        
-        // 等待接收 MessageEvent
-        while let Some(data) = receiver.recv().await {
-            match data {
-                ApiChannelItem::NoticeEvent(event) => {
-                    if notice_type.is_none() && sub_type.is_none() {
-                        return Some(event);
-                    }
-                    
-                    if let Some(n) = &notice_type {
-                        if &event.notice_type == n {
-                            return Some(event);
-                        }
-                    }
-                    
-                    if let Some(n) = &sub_type {
-                        if let Some(s) = &event.sub_type {
-                            if s == n {
-                                return Some(event);
-                            }
-                        }
-                    }
-                    return None;
-                }
-                ApiChannelItem::TimeOut => {
-                    event!(Level::DEBUG, "Temp Notice Matcher TimeOut");
-                    return None;
-                }
-                // 中转 temp Matcher 的 Remove Action
-                // ApiChannelItem::Action(action) => self.set(action).await,
-                _ => {
-                    event!(
-                        Level::WARN,
-                        "{}",
-                        "Temp Message Matcher接受端接收到错误Api或Action消息".bright_red()
-                    );
-                } // 忽视 event 该 receiver 永不应该收到 event
+
+        match wait.await {
+            Ok(event) => Some(event),
+            Err(e) => {
+                event!(Level::DEBUG, "Temp Notice Matcher: {}", e);
+                None
             }
         }
-        None
     }
     /// 发送 Vec<Message> 消息 带 message_id
     pub async fn send(&self, msg: crate::message::MessageChain) -> Option<crate::api_resp::MessageId> {
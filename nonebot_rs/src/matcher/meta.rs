@@ -0,0 +1,91 @@
+use crate::config::BotConfig;
+use crate::event::{Role, UserId};
+use crate::matcher::MessageContent;
+
+/// Matcher 在帮助中的可见性
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Visibility {
+    /// 所有人可见
+    #[default]
+    Public,
+    /// 群管理员、群主与 superuser 可见
+    Admin,
+    /// 仅 superuser 可见
+    SuperUser,
+    /// 不在帮助中显示
+    Hidden,
+}
+
+impl std::str::FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "public" => Ok(Visibility::Public),
+            "admin" => Ok(Visibility::Admin),
+            "superuser" => Ok(Visibility::SuperUser),
+            "hidden" => Ok(Visibility::Hidden),
+            _ => Err(format!("unknown visibility: {}", s)),
+        }
+    }
+}
+
+impl Visibility {
+    /// 根据消息事件发送者身份获取其可见的最高等级
+    pub fn of_sender<E>(event: &E, config: &BotConfig) -> Visibility
+    where
+        E: MessageContent + UserId,
+    {
+        let user_id = event.get_user_id().to_string();
+        if config.superusers.iter().any(|s| s == &user_id) {
+            return Visibility::SuperUser;
+        }
+        match event.sender_role() {
+            Some(Role::Owner) | Some(Role::Admin) => Visibility::Admin,
+            _ => Visibility::Public,
+        }
+    }
+
+    /// 当前等级的用户能否看到 visibility 等级的 Matcher
+    pub fn can_see(&self, visibility: Visibility) -> bool {
+        visibility != Visibility::Hidden && visibility <= *self
+    }
+}
+
+/// Matcher 元信息，用于生成帮助
+#[derive(Debug, Clone, Default)]
+pub struct MatcherMeta {
+    /// 命令名与别名，首个为主命令名
+    pub commands: Vec<String>,
+    /// 简介
+    pub description: Option<String>,
+    /// 用法
+    pub usage: Option<String>,
+    /// 示例
+    pub examples: Vec<String>,
+    /// 分类
+    pub category: Option<String>,
+    /// 可见性
+    pub visibility: Visibility,
+}
+
+/// 注册于 Matchers 的 Matcher 概要信息
+#[derive(Debug, Clone)]
+pub struct MatcherInfo {
+    pub name: String,
+    pub priority: i8,
+    pub disable: bool,
+    pub meta: MatcherMeta,
+}
+
+impl MatcherInfo {
+    /// 是否匹配名称或任一命令名
+    pub fn is_name(&self, name: &str) -> bool {
+        self.name == name || self.meta.commands.iter().any(|c| c == name)
+    }
+
+    /// 帮助中显示的名称，优先使用主命令名
+    pub fn display_name(&self) -> &str {
+        self.meta.commands.first().unwrap_or(&self.name)
+    }
+}
@@ -0,0 +1,211 @@
+use crate::bot::Bot;
+use crate::event::Event;
+use async_trait::async_trait;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Handler 运行结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandlerOutcome {
+    /// 正常结束
+    Completed,
+    /// Handler panic，附带 panic 信息
+    Panicked(String),
+    /// Handler 任务被取消
+    Cancelled,
+    /// 超过最长运行时间被取消
+    TimedOut,
+    /// 超出并发上限被拒绝，未运行
+    Rejected,
+}
+
+/// 单次 Handler 运行记录
+#[derive(Debug, Clone)]
+pub struct HandlerRecord {
+    /// Matcher 名称
+    pub matcher_name: String,
+    pub self_id: i64,
+    /// 私聊或无用户事件为 0
+    pub user_id: i64,
+    /// 私聊或无群事件为 0
+    pub group_id: i64,
+    /// 运行结果
+    pub outcome: HandlerOutcome,
+    /// 运行耗时
+    pub elapsed: Duration,
+}
+
+/// Matchers 分发中间件
+///
+/// 按注册顺序依次调用：
+/// - `before_match` 在匹配前调用，可修改 event，返回 false 丢弃该事件
+/// - `after_handle` 在每个 Handler 运行结束后调用
+/// - `after_dispatch` 在事件分发完成后调用，获得所有匹配成功的 Matcher 名称
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// 中间件名称
+    fn name(&self) -> &str;
+
+    async fn before_match(&self, _event: &mut Event, _bot: &Bot) -> bool {
+        true
+    }
+
+    async fn after_handle(&self, _record: &HandlerRecord) {}
+
+    async fn after_dispatch(&self, _event: &Event, _bot: &Bot, _matched: &[String]) {}
+}
+
+/// 有序中间件链，可在运行期间由其他 Plugin 持有并注册
+#[derive(Clone, Default)]
+pub struct Middlewares {
+    inner: Arc<RwLock<Vec<Arc<dyn Middleware>>>>,
+}
+
+impl std::fmt::Debug for Middlewares {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<String> = self.snapshot().iter().map(|m| m.name().to_string()).collect();
+        f.debug_tuple("Middlewares").field(&names).finish()
+    }
+}
+
+impl Middlewares {
+    /// 追加中间件至链尾
+    pub fn add<M>(&self, middleware: M)
+    where
+        M: Middleware + 'static,
+    {
+        self.inner.write().unwrap().push(Arc::new(middleware));
+    }
+
+    /// 根据名称移除中间件
+    pub fn remove(&self, name: &str) {
+        self.inner.write().unwrap().retain(|m| m.name() != name);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().unwrap().is_empty()
+    }
+
+    /// 获取当前中间件链快照
+    pub fn snapshot(&self) -> Vec<Arc<dyn Middleware>> {
+        self.inner.read().unwrap().clone()
+    }
+
+    /// 依次调用 before_match，任一返回 false 即丢弃事件
+    pub async fn before_match(&self, event: &mut Event, bot: &Bot) -> bool {
+        for middleware in self.snapshot() {
+            if !middleware.before_match(event, bot).await {
+                tracing::event!(
+                    tracing::Level::DEBUG,
+                    "Event dropped by middleware {}",
+                    middleware.name()
+                );
+                return false;
+            }
+        }
+        true
+    }
+
+    pub async fn after_handle(&self, record: &HandlerRecord) {
+        for middleware in self.snapshot() {
+            middleware.after_handle(record).await;
+        }
+    }
+
+    pub async fn after_dispatch(&self, event: &Event, bot: &Bot, matched: &[String]) {
+        for middleware in self.snapshot() {
+            middleware.after_dispatch(event, bot, matched).await;
+        }
+    }
+}
+
+/// 从 panic payload 中提取信息
+pub(crate) fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[tokio::test]
+async fn middleware_test() {
+    use std::sync::Mutex;
+
+    struct Record {
+        name: &'static str,
+        pass: bool,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Middleware for Record {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn before_match(&self, _: &mut Event, _: &Bot) -> bool {
+            self.calls.lock().unwrap().push(format!("before {}", self.name));
+            self.pass
+        }
+
+        async fn after_handle(&self, record: &HandlerRecord) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("after {} {}", self.name, record.matcher_name));
+        }
+    }
+
+    let (api_sender, _api_receiver) = tokio::sync::mpsc::channel(1);
+    let (action_sender, _action_receiver) = tokio::sync::mpsc::channel(1);
+    let (_resp_sender, resp_receiver) = tokio::sync::watch::channel(
+        serde_json::from_str(r#"{"status": "ok", "retcode": 0, "data": null, "echo": ""}"#)
+            .unwrap(),
+    );
+    let bot = Bot::new(
+        1,
+        crate::config::BotConfig::default(),
+        api_sender,
+        action_sender,
+        resp_receiver,
+    );
+    let mut event: Event = serde_json::from_str(
+        r#"{"group_id":101,"message_id":111,"notice_type":"group_recall","operator_id":11,"post_type":"notice","self_id":11,"time":1631193409,"user_id":11}"#,
+    )
+    .unwrap();
+
+    let calls = Arc::new(Mutex::new(vec![]));
+    let middlewares = Middlewares::default();
+    for (name, pass) in [("first", true), ("drop", false), ("last", true)] {
+        middlewares.add(Record {
+            name,
+            pass,
+            calls: calls.clone(),
+        });
+    }
+    // 按注册顺序调用，返回 false 后不再调用后续中间件
+    assert!(!middlewares.before_match(&mut event, &bot).await);
+    assert_eq!(*calls.lock().unwrap(), ["before first", "before drop"]);
+
+    calls.lock().unwrap().clear();
+    middlewares.remove("drop");
+    assert!(middlewares.before_match(&mut event, &bot).await);
+    middlewares
+        .after_handle(&HandlerRecord {
+            matcher_name: "echo".to_string(),
+            self_id: 11,
+            user_id: 11,
+            group_id: 101,
+            outcome: HandlerOutcome::Completed,
+            elapsed: Duration::ZERO,
+        })
+        .await;
+    assert_eq!(
+        *calls.lock().unwrap(),
+        ["before first", "before last", "after first echo", "after last echo"]
+    );
+    assert_eq!(format!("{:?}", middlewares), r#"Middlewares(["first", "last"])"#);
+}
@@ -1,9 +1,12 @@
+use crate::bot::Replyable;
 use crate::config::BotConfig;
-use crate::event::{GroupMessageEvent, MessageEvent, NoticeEvent, PrivateMessageEvent, SelfId};
+use crate::event::{GroupId, GroupMessageEvent, MessageEvent, NoticeEvent, PrivateMessageEvent, SelfId, UserId};
 use crate::utils::timestamp;
 use crate::{Action, Message};
 use async_trait::async_trait;
+use futures_util::FutureExt;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::warn;
@@ -12,14 +15,36 @@ use crate::message::MessageChain;
 
 mod action;
 #[doc(hidden)]
+pub mod access;
+#[doc(hidden)]
 pub mod api;
 #[doc(hidden)]
+pub mod command;
+#[doc(hidden)]
+pub mod conversation;
+#[doc(hidden)]
+pub mod cooldown;
+#[doc(hidden)]
+pub mod error_hook;
+#[doc(hidden)]
+pub mod extract;
+#[doc(hidden)]
+pub mod limit;
+#[doc(hidden)]
 pub mod matchers;
 #[doc(hidden)]
+pub mod meta;
+#[doc(hidden)]
+pub mod middleware;
+#[doc(hidden)]
 pub mod message_event_matcher;
 
+#[doc(hidden)]
+pub mod session;
 #[doc(hidden)]
 pub mod set_get;
+#[doc(hidden)]
+pub mod switch;
 /// 内建 rules
 #[cfg(feature = "matcher")]
 #[cfg_attr(docsrs, doc(cfg(feature = "matcher")))]
@@ -30,8 +55,28 @@ pub mod pre_matchers;
 #[doc(hidden)]
 pub mod request_event_matcher;
 #[doc(hidden)]
+pub mod wait;
+#[doc(hidden)]
 pub mod notice_event_matcher;
 
+pub use access::{AccessControl, AccessEntry, AccessKind, AccessTarget};
+pub use command::{
+    parse_value, shell_split, ArgSpec, CommandArgs, CommandError, CommandErrorKind, CommandHandler,
+    CommandSpec, OnCommand, OptionSpec, ParsedCommand,
+};
+pub use conversation::{Conversation, Slot, Slots, Validator};
+pub use cooldown::{Cooldown, CooldownScope};
+pub use error_hook::{ErrorCallback, ErrorContext, ErrorHook, HandlerFailure};
+pub use extract::{
+    ExtractContext, FromEvent, Images, MessageContent, PlainText, PluginConfig, RegexCaptures,
+};
+pub use limit::{ConcurrencyLimit, Limits, Overflow, Permit, Ticket, DEFAULT_MAX_QUEUE};
+pub use meta::{MatcherInfo, MatcherMeta, Visibility};
+pub use middleware::{HandlerOutcome, HandlerRecord, Middleware, Middlewares};
+pub use session::{SessionKey, SessionRegistry};
+pub use switch::{MatcherSwitches, SwitchScope};
+pub use wait::{WaitError, WaitEvent};
+
 /// rule 函数类型
 pub type Rule<E> = Arc<dyn Fn(&E, &BotConfig) -> bool + Send + Sync>;
 /// permatcher 函数类型
@@ -51,6 +96,16 @@ where
     pub bot: Option<crate::bot::Bot>,
     /// Matchers Action Sender
     action_sender: Option<matchers::ActionSender>,
+    /// 会话注册表
+    sessions: Option<SessionRegistry>,
+    /// 运行期开关
+    switches: Option<MatcherSwitches>,
+    /// 错误处理
+    error_hook: Option<ErrorHook>,
+    /// Handler 并发上限
+    pub concurrency: Option<ConcurrencyLimit>,
+    /// Handler 最长运行秒数
+    pub handle_timeout: Option<u64>,
     /// Matcher 的匹配优先级
     pub priority: i8,
     /// 前处理函数组，获取 &mut event
@@ -67,6 +122,10 @@ where
     pub temp: bool,
     /// 过期时间戳
     pub timeout: Option<i64>,
+    /// 冷却设置
+    pub cooldown: Option<Cooldown>,
+    /// 元信息
+    pub meta: MatcherMeta,
     
     #[doc(hidden)]
     event: Option<E>,
@@ -89,6 +148,10 @@ where
             .field("disable", &self.disable)
             .field("temp", &self.temp)
             .field("timeout", &self.timeout)
+            .field("cooldown", &self.cooldown)
+            .field("concurrency", &self.concurrency)
+            .field("handle_timeout", &self.handle_timeout)
+            .field("meta", &self.meta)
             .field("bot", &self.bot)
             .finish()
     }
@@ -107,9 +170,17 @@ where
     /// timeout drop 函数
     fn timeout_drop(&self, _: &Matcher<E>) {}
     /// 匹配函数
-    fn match_(&mut self, event: &mut E) -> bool;
+    fn match_(&self, event: &mut E) -> bool;
     /// 处理函数
     async fn handle(&self, event: E, matcher: &mut Matcher<E>);
+    /// Handler 预设的冷却设置，`Matcher::new` 时读取
+    fn cooldown(&self) -> Option<Cooldown> {
+        None
+    }
+    /// Handler 预设的元信息，`Matcher::new` 时读取
+    fn meta(&self) -> MatcherMeta {
+        MatcherMeta::default()
+    }
     /// Load config
     #[allow(unused_variables)]
     fn load_config(&mut self, config: HashMap<String, toml::Value>) {}
@@ -134,6 +205,10 @@ where
     ///     disable: false,
     ///     temp: false,
     ///     timeout: None,
+    ///     cooldown: handler.cooldown(),
+    ///     concurrency: None,
+    ///     handle_timeout: None,
+    ///     meta: handler.meta(),
     ///     event: None,
     /// }
     /// ```
@@ -142,11 +217,18 @@ where
         H: Handler<E> + Sync + Send + 'static,
     {
 
+        let cooldown = handler.cooldown();
+        let meta = handler.meta();
         // 默认 Matcher
         Matcher {
             name: name.to_string(),
             bot: None,
             action_sender: None,
+            sessions: None,
+            switches: None,
+            error_hook: None,
+            concurrency: None,
+            handle_timeout: None,
             priority: 1,
             pre_matchers: vec![],
             rules: vec![],
@@ -155,12 +237,14 @@ where
             disable: false,
             temp: false,
             timeout: None,
+            cooldown,
+            meta,
             event: None,
         }
     }
 
     #[doc(hidden)]
-    fn pre_matcher_handle(&self, event: &mut E, config: BotConfig) -> bool {
+    fn pre_matcher_handle(&self, event: &mut E, config: &BotConfig) -> bool {
         // 遍历 pre_matcher 处理
         for premather in &self.pre_matchers {
             if !premather(event, config.clone()) {
@@ -185,53 +269,191 @@ where
     #[doc(hidden)]
     pub async fn match_(
         &self,
-        event: E,
-        config: BotConfig,
+        event: &E,
+        bot: &crate::bot::Bot,
         matchers: &mut matchers::Matchers,
     ) -> bool
     where
-        E: Send + 'static + SelfId,
+        E: Send + Sync + 'static + SelfId + UserId + GroupId + Replyable + Into<crate::event::Event>,
     {
         // Matcher 处理流程，匹配成功返回 true 并行处理 handler
-        let mut event = event.clone();
         if let Some(timeout) = self.timeout {
             if timestamp() > timeout {
                 matchers.remove_matcher(&self.name);
                 {
                     let handler = self.handler.read().await;
-                    handler.timeout_drop(&self);
+                    handler.timeout_drop(&self.build(bot.clone()));
                 }
                 return false;
             }
         }
 
-        if self.disable {
+        if !self.is_enabled(event.get_group_id()) {
             return false;
         }
 
-        if !self.pre_matcher_handle(&mut event, config.clone()) {
+        // pre_matcher 与 handler 可能修改 event，每个 Matcher 仅在此复制一次
+        let mut event = event.clone();
+        if !self.pre_matcher_handle(&mut event, &bot.config) {
             return false;
         }
 
-        if !self.check_rules(&event, &config) {
+        if !self.check_rules(&event, &bot.config) {
             return false;
         }
         
         {
-            let mut handler = self.handler.write().await;
+            let handler = self.handler.read().await;
             if !handler.match_(&mut event) {
                 return false;
             }
-            let mut matcher = self.clone().set_event(&event);
+        }
+        if let Some(cooldown) = &self.cooldown {
+            if let Some(remaining) = cooldown.check(&event) {
+                tracing::event!(
+                    tracing::Level::DEBUG,
+                    "Matcher {} is cooling down, {}s remaining",
+                    self.name,
+                    remaining
+                );
+                if let Some(reply) = cooldown.reply_text(remaining) {
+                    let bot = bot.clone();
+                    tokio::spawn(async move {
+                        event.reply_by(&bot, vec![Message::text(reply)]).await
+                    });
+                }
+                return true;
+            }
+        }
+        {
+            // 仅在匹配成功时复制 Matcher 交给 handler
+            let mut matcher = self.clone();
+            if matcher.bot.is_none() {
+                matcher.bot = Some(bot.clone());
+            }
+            matcher.event = Some(event.clone());
             let handler = self.handler.clone();
+            let middlewares = matchers.get_middlewares();
+            let limits = matchers.get_limits();
+            let local_limit = self.concurrency.clone();
+            // 临时 Matcher 负责向等待中的 Handler 转发事件，不受全局并发限制，避免互相等待
+            let global_limit = if self.temp { None } else { limits.concurrency() };
+            let handle_timeout = self.handle_timeout.or(limits.handle_timeout());
+            let error_hook = self.error_hook.clone().unwrap_or_default();
+            let bot = matcher.bot.clone();
+            let matcher_name = self.name.clone();
+            let (self_id, user_id, group_id) =
+                (event.get_self_id(), event.get_user_id(), event.get_group_id());
+            let error_event = event.clone();
+            // spawn 前占用名额，超出排队上限的事件直接拒绝，不为其创建任务
+            let mut tickets = vec![];
+            for limit in local_limit.iter().chain(global_limit.iter()) {
+                match limit.reserve() {
+                    Some(ticket) => tickets.push(ticket),
+                    None => {
+                        tracing::event!(
+                            tracing::Level::WARN,
+                            "Matcher {} rejected: concurrency limit {} reached",
+                            matcher_name,
+                            limit.max
+                        );
+                        if !middlewares.is_empty() {
+                            let record = HandlerRecord {
+                                matcher_name,
+                                self_id,
+                                user_id,
+                                group_id,
+                                outcome: HandlerOutcome::Rejected,
+                                elapsed: std::time::Duration::default(),
+                            };
+                            middlewares.after_handle(&record).await;
+                        }
+                        return true;
+                    }
+                }
+            }
             tokio::spawn(async move {
-                let handler = handler.read().await;
-                handler.handle(event, &mut matcher).await
+                // 先获取 Matcher 许可再获取全局许可，避免排队时占用全局并发
+                let mut permits = vec![];
+                for ticket in tickets {
+                    match ticket.acquire().await {
+                        Some(permit) => permits.push(permit),
+                        None => return,
+                    }
+                }
+                let start = std::time::Instant::now();
+                let handle = std::panic::AssertUnwindSafe(async move {
+                    let handler = handler.read().await;
+                    handler.handle(event, &mut matcher).await
+                })
+                .catch_unwind();
+                let result = match handle_timeout {
+                    Some(seconds) => {
+                        tokio::time::timeout(std::time::Duration::from_secs(seconds), handle)
+                            .await
+                            .ok()
+                    }
+                    None => Some(handle.await),
+                };
+                drop(permits);
+                let outcome = match result {
+                    Some(Ok(())) => HandlerOutcome::Completed,
+                    Some(Err(payload)) => {
+                        let message = middleware::panic_message(payload);
+                        let context = ErrorContext {
+                            matcher_name: matcher_name.clone(),
+                            event: Some(error_event.into()),
+                            failure: HandlerFailure::Panic {
+                                message: message.clone(),
+                                backtrace: error_hook::take_panic_backtrace(),
+                            },
+                        };
+                        error_hook.report(context, bot.as_ref()).await;
+                        HandlerOutcome::Panicked(message)
+                    }
+                    None => {
+                        let context = ErrorContext {
+                            matcher_name: matcher_name.clone(),
+                            event: Some(error_event.into()),
+                            failure: HandlerFailure::Error(crate::NBError::Timeout),
+                        };
+                        error_hook.report(context, bot.as_ref()).await;
+                        HandlerOutcome::TimedOut
+                    }
+                };
+                if !middlewares.is_empty() {
+                    let record = HandlerRecord {
+                        matcher_name,
+                        self_id,
+                        user_id,
+                        group_id,
+                        outcome,
+                        elapsed: start.elapsed(),
+                    };
+                    middlewares.after_handle(&record).await;
+                }
             });
         }
         return true;
     }
 
+    /// 将 Handler 错误交由 Matchers 统一处理
+    pub async fn report_error(&self, error: crate::NBError)
+    where
+        E: Into<crate::event::Event>,
+    {
+        let context = ErrorContext {
+            matcher_name: self.name.clone(),
+            event: self.event.clone().map(|e| e.into()),
+            failure: HandlerFailure::Error(error),
+        };
+        self.error_hook
+            .clone()
+            .unwrap_or_default()
+            .report(context, self.bot.as_ref())
+            .await;
+    }
+
     /// 发送 nbrs 内部设置 Action
     pub async fn set(&self, set: Action) {
         if let Some(bot) = &self.bot {
@@ -239,26 +461,104 @@ where
         }
     }
     
-    /// 向 Matchers 添加 `Matcher<MessageEvent>`
-    pub async fn set_message_matcher(&self, matcher: Matcher<MessageEvent>) {
-        let action = action::MatchersAction::AddMessageEventMatcher {
-            message_event_matcher: matcher,
-        };
+    /// 发送 Matchers Action 并等待处理，返回 Action 是否生效
+    async fn send_matchers_action(&self, action: action::MatchersAction) -> bool {
         if let Some(action_sender) = &self.action_sender {
-            action_sender.send(action).unwrap();
+            match action_sender.send_ack(action).await {
+                Some(applied) => applied,
+                None => {
+                    tracing::event!(tracing::Level::WARN, "Matchers is not running.");
+                    false
+                }
+            }
         } else {
-            tracing::event!(tracing::Level::WARN, "Action Sender not init.")
+            tracing::event!(tracing::Level::WARN, "Action Sender not init.");
+            false
         }
     }
-    /// 向 Matchers 添加 `Matcher<NoticeEvent>`
-    pub async fn set_notice_matcher(&self, matcher: Matcher<NoticeEvent>) {
-        let action = action::MatchersAction::AddNoticeEventMatcher {
+
+    /// 向 Matchers 添加 `Matcher<MessageEvent>`，返回时已生效
+    pub async fn set_message_matcher(&self, matcher: Matcher<MessageEvent>) -> bool {
+        self.send_matchers_action(action::MatchersAction::AddMessageEventMatcher {
+            message_event_matcher: matcher,
+        })
+        .await
+    }
+
+    /// 向 Matchers 添加 `Matcher<NoticeEvent>`，返回时已生效
+    pub async fn set_notice_matcher(&self, matcher: Matcher<NoticeEvent>) -> bool {
+        self.send_matchers_action(action::MatchersAction::AddNoticeEventMatcher {
             notice_event_matcher: matcher,
-        };
+        })
+        .await
+    }
+
+    /// 根据名称从 Matchers 移除 Matcher，返回 Matcher 是否存在
+    pub async fn remove_matcher(&self, name: &str) -> bool {
+        self.send_matchers_action(action::MatchersAction::RemoveMatcher {
+            matcher_name: name.to_string(),
+        })
+        .await
+    }
+
+    /// 根据名称启用或禁用 Matcher，返回 Matcher 是否存在
+    pub async fn disable_matcher(&self, name: &str, disable: bool) -> bool {
+        self.send_matchers_action(action::MatchersAction::DisableMatcher {
+            matcher_name: name.to_string(),
+            disable,
+        })
+        .await
+    }
+}
+
+static TEMP_ID: AtomicU64 = AtomicU64::new(0);
+
+/// 生成唯一的临时 Matcher 名称
+fn temp_matcher_name(self_id: i64, user_id: i64) -> String {
+    format!(
+        "temp-{}-{}-{}",
+        self_id,
+        user_id,
+        TEMP_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// 临时 Matcher 守卫，drop 时移除临时 Matcher 并释放其占用的会话
+///
+/// 等待中的 Handler 因超时被取消时同样生效，避免临时 Matcher 与会话泄漏
+pub(crate) struct TempMatcherGuard {
+    name: String,
+    action_sender: Option<matchers::ActionSender>,
+    session: Option<(SessionRegistry, SessionKey)>,
+}
+
+impl TempMatcherGuard {
+    pub(crate) fn new(name: &str, action_sender: Option<matchers::ActionSender>) -> Self {
+        TempMatcherGuard {
+            name: name.to_string(),
+            action_sender,
+            session: None,
+        }
+    }
+
+    /// drop 时一并释放该会话（仅当仍由本 Matcher 占用）
+    pub(crate) fn set_session(&mut self, sessions: SessionRegistry, key: SessionKey) {
+        self.session = Some((sessions, key));
+    }
+}
+
+impl Drop for TempMatcherGuard {
+    fn drop(&mut self) {
+        if let Some((sessions, key)) = &self.session {
+            sessions.release_owned(key, &self.name);
+        }
         if let Some(action_sender) = &self.action_sender {
-            action_sender.send(action).unwrap();
-        } else {
-            tracing::event!(tracing::Level::WARN, "Action Sender not init.")
+            // 已被移除或 Matchers 已停止时忽略
+            action_sender
+                .send(action::MatchersAction::RemoveMatcher {
+                    matcher_name: self.name.clone(),
+                })
+                .ok();
         }
     }
 }
@@ -275,12 +575,7 @@ where
 {
     use crate::event::UserId;
     let mut m = Matcher::new(
-        &format!(
-            "{}-{}-{}",
-            event.get_self_id(),
-            event.get_user_id(),
-            event.get_time()
-        ),
+        &temp_matcher_name(event.get_self_id(), event.get_user_id()),
         handler,
     )
         .add_rule(rules::is_bot(event.get_self_id()));
@@ -311,11 +606,7 @@ pub fn build_temp_notice_event_matcher<H>(
 {
     use crate::event::UserId;
     let mut m = Matcher::new(
-        &format!(
-            "{}-{}",
-            event.get_self_id(),
-            event.get_user_id(),
-        ),
+        &temp_matcher_name(event.get_self_id(), event.get_user_id()),
         handler,
     )
         .add_rule(rules::is_bot(event.get_self_id()));
@@ -375,41 +666,76 @@ impl<'a> From<&'a mut GroupMessageEvent> for HandEvent<'a> {
     }
 }
 
-pub fn match_event_args_all(args: Vec<EventArg>, event: HandEvent) -> bool {
-    for x in args {
-        if !match_event_item(x, event.clone()) {
-            return false;
+/// 预编译的 EventArg 表达式
+///
+/// 同一层级的正则合并为一个 `RegexSet`，仅在构建时编译一次
+#[derive(Clone, Debug)]
+pub struct EventArgs {
+    /// 为 true 时该层所有条件均不能匹配（Not），否则均需匹配（All）
+    not: bool,
+    regexps: Option<regex::RegexSet>,
+    children: Vec<EventArgs>,
+}
+
+impl EventArgs {
+    /// 编译表达式，所有条件均需匹配，正则不合法时返回错误
+    pub fn new(args: Vec<EventArg>) -> Result<Self, regex::Error> {
+        Self::compile(false, args)
+    }
+
+    fn compile(not: bool, args: Vec<EventArg>) -> Result<Self, regex::Error> {
+        let mut patterns = vec![];
+        let mut children = vec![];
+        for arg in args {
+            match arg {
+                EventArg::All(v) => children.push(Self::compile(false, v)?),
+                EventArg::Not(v) => children.push(Self::compile(true, v)?),
+                EventArg::Regexp(pattern) => patterns.push(pattern),
+            }
         }
+        let regexps = if patterns.is_empty() {
+            None
+        } else {
+            Some(regex::RegexSet::new(patterns)?)
+        };
+        Ok(EventArgs {
+            not,
+            regexps,
+            children,
+        })
     }
-    // 一个条件都没有认为是true
-    true
-}
 
-fn match_event_args_not(args: Vec<EventArg>, event: HandEvent) -> bool {
-    for x in args {
-        if match_event_item(x, event.clone()) {
-            return false;
+    pub fn is_match(&self, content: &str) -> bool {
+        if self.not {
+            // 任一条件匹配即失败
+            !self.regexps.as_ref().is_some_and(|set| set.is_match(content))
+                && !self.children.iter().any(|child| child.is_match(content))
+        } else {
+            // 一个条件都没有认为是true
+            self.regexps
+                .iter()
+                .all(|set| set.matches(content).iter().count() == set.len())
+                && self.children.iter().all(|child| child.is_match(content))
         }
     }
-    true
 }
 
-fn match_event_args_regexp(args: String, event: HandEvent) -> bool {
-    regex::Regex::new(args.as_str()).expect("正则表达式构建错误").is_match(event.content().as_str())
+pub fn match_event_args_all(args: &EventArgs, event: HandEvent) -> bool {
+    args.is_match(event.content())
 }
 
-fn match_event_item(arg: EventArg, event: HandEvent) -> bool {
-    match arg {
-        EventArg::All(v) => match_event_args_all(v, event.clone()),
-        EventArg::Not(v) => match_event_args_not(v, event.clone()),
-        EventArg::Regexp(v) => match_event_args_regexp(v, event.clone()),
-    }
-}
+/// 命令参数分隔符
+static WHITESPACE: once_cell::sync::Lazy<regex::Regex> =
+    once_cell::sync::Lazy::new(|| regex::Regex::new("\\s+").unwrap());
 
 pub struct CommandMatcher {
     pub idx: usize,
     pub elements: MessageChain,
     pub matching: String,
+    /// 参数解析失败的原因，由 `FromCommandMatcher` 实现设置
+    pub error: Option<CommandError>,
+    /// 当前解析的参数名，由 bot_command 宏在解析每个参数前设置
+    pub param: Option<&'static str>,
 }
 
 impl CommandMatcher {
@@ -418,6 +744,8 @@ impl CommandMatcher {
             idx: 0,
             elements: value,
             matching: String::new(),
+            error: None,
+            param: None,
         };
         matcher.push_text();
         matcher
@@ -442,8 +770,7 @@ impl CommandMatcher {
     }
 
     pub fn match_command(&mut self, command_name: &str) -> bool {
-        let sp_regexp = regex::Regex::new("\\s+").expect("nonebot_rs 正则错误");
-        let mut sp = sp_regexp.split(self.matching.as_str());
+        let mut sp = WHITESPACE.split(self.matching.as_str());
         if let Some(first) = sp.next() {
             if command_name.eq(first) {
                 self.matching = self.matching[first.len()..].trim().to_string();
@@ -453,6 +780,32 @@ impl CommandMatcher {
         return false;
     }
 
+    /// 匹配命令名或任一别名
+    pub fn match_commands(&mut self, command_names: &[&str]) -> bool {
+        command_names.iter().any(|name| self.match_command(name))
+    }
+
+    /// 记录缺少参数，未设置参数名时使用类型名
+    pub fn missing_argument(&mut self, ty: &str) {
+        let name = self.param.unwrap_or(ty).to_string();
+        self.error = Some(CommandError::new(
+            CommandErrorKind::MissingArgument(name),
+            String::new(),
+        ));
+    }
+
+    /// 记录参数值无法解析，未设置参数名时使用类型名
+    pub fn invalid_value(&mut self, ty: &str, value: &str) {
+        let name = self.param.unwrap_or(ty).to_string();
+        self.error = Some(CommandError::new(
+            CommandErrorKind::InvalidValue {
+                name,
+                value: value.to_string(),
+            },
+            String::new(),
+        ));
+    }
+
     pub fn not_blank(&self) -> bool {
         !self.matching.is_empty() || self.idx < self.elements.len()
     }
@@ -522,10 +875,10 @@ pub fn matcher_get<F: Sized + FromCommandMatcher>(matcher: &mut CommandMatcher)
 impl FromCommandMatcher for String {
     fn get(matcher: &mut CommandMatcher) -> Option<Self> {
         if matcher.matching.is_empty() {
+            matcher.missing_argument("String");
             return None;
         }
-        let sp_regexp = regex::Regex::new("\\s+").expect("nonebot_rs 正则错误");
-        let mut sp = sp_regexp.split(matcher.matching.as_str());
+        let mut sp = WHITESPACE.split(matcher.matching.as_str());
         if let Some(first) = sp.next() {
             let result = Some(first.to_string());
             matcher.matching = matcher.matching[first.len()..].trim().to_string();
@@ -541,8 +894,7 @@ impl FromCommandMatcher for Option<String> {
         if matcher.matching.is_empty() {
             return Some(result);
         }
-        let sp_regexp = regex::Regex::new("\\s+").expect("nonebot_rs 正则错误");
-        let mut sp = sp_regexp.split(matcher.matching.as_str());
+        let mut sp = WHITESPACE.split(matcher.matching.as_str());
         if let Some(first) = sp.next() {
             result = Some(first.to_string());
             matcher.matching = matcher.matching[first.len()..].trim().to_string();
@@ -553,8 +905,7 @@ impl FromCommandMatcher for Option<String> {
 
 impl FromCommandMatcher for Vec<String> {
     fn get(matcher: &mut CommandMatcher) -> Option<Self> {
-        let sp_regexp = regex::Regex::new("\\s+").expect("nonebot_rs 正则错误");
-        let result = sp_regexp
+        let result = WHITESPACE
             .split(matcher.matching.as_str())
             .filter_map(|s| {
                 if !s.is_empty() {
@@ -574,14 +925,18 @@ macro_rules! command_base_ty_supplier {
         impl FromCommandMatcher for $ty {
             fn get(matcher: &mut CommandMatcher) -> Option<$ty> {
                 if matcher.matching.is_empty() {
+                    matcher.missing_argument(stringify!($ty));
                     return None;
                 }
-                let sp_regexp = regex::Regex::new("\\s+").expect("nonebot_rs 正则错误");
-                let mut sp = sp_regexp.split(matcher.matching.as_str());
+                let mut sp = WHITESPACE.split(matcher.matching.as_str());
                 if let Some(first) = sp.next() {
                     let result = match first.parse::<$ty>() {
                         Ok(value) => Some(value),
-                        Err(_) => return None,
+                        Err(_) => {
+                            let first = first.to_string();
+                            matcher.invalid_value(stringify!($ty), &first);
+                            return None;
+                        }
                     };
                     matcher.matching = matcher.matching[first.len()..].trim().to_string();
                     return result;
@@ -596,8 +951,7 @@ macro_rules! command_base_ty_supplier {
                 if matcher.matching.is_empty() {
                     return Some(result);
                 }
-                let sp_regexp = regex::Regex::new("\\s+").expect("nonebot_rs 正则错误");
-                let mut sp = sp_regexp.split(matcher.matching.as_str());
+                let mut sp = WHITESPACE.split(matcher.matching.as_str());
                 if let Some(first) = sp.next() {
                     match first.parse::<$ty>() {
                         Ok(value) => {
@@ -617,8 +971,7 @@ macro_rules! command_base_ty_supplier {
                 if matcher.matching.is_empty() {
                     return Some(result);
                 }
-                let sp_regexp = regex::Regex::new("\\s+").expect("nonebot_rs 正则错误");
-                let sp = sp_regexp.split(matcher.matching.as_str());
+                let sp = WHITESPACE.split(matcher.matching.as_str());
                 let mut new_matching = vec![];
                 for x in sp {
                     if !new_matching.is_empty() {
@@ -664,10 +1017,8 @@ macro_rules! command_rq_element_ty_supplier {
     ($ty:ty, $mat:path) => {
         impl FromCommandMatcher for $ty {
             fn get(matcher: &mut CommandMatcher) -> Option<Self> {
-                if !matcher.matching.is_empty() {
-                    return None;
-                }
-                if matcher.idx >= matcher.elements.len() {
+                if !matcher.matching.is_empty() || matcher.idx >= matcher.elements.len() {
+                    matcher.missing_argument(stringify!($ty));
                     return None;
                 }
                 match message_to_cq(matcher.elements.get(matcher.idx).unwrap().clone()) {
@@ -676,7 +1027,10 @@ macro_rules! command_rq_element_ty_supplier {
                         matcher.push_text();
                         Some(i)
                     }
-                    _ => None,
+                    _ => {
+                        matcher.missing_argument(stringify!($ty));
+                        None
+                    }
                 }
             }
         }
@@ -743,10 +1097,8 @@ command_rq_element_ty_supplier!(JsonMsg, CqCode::JsonMsg);
 
 impl FromCommandMatcher for Image {
     fn get(matcher: &mut CommandMatcher) -> Option<Self> {
-        if !matcher.matching.is_empty() {
-            return None;
-        }
-        if matcher.idx >= matcher.elements.len() {
+        if !matcher.matching.is_empty() || matcher.idx >= matcher.elements.len() {
+            matcher.missing_argument("Image");
             return None;
         }
         match message_to_cq(matcher.elements.get(matcher.idx).unwrap().clone()) {
@@ -755,7 +1107,10 @@ impl FromCommandMatcher for Image {
                 matcher.push_text();
                 Some(i)
             }
-            _ => None,
+            _ => {
+                matcher.missing_argument("Image");
+                None
+            }
         }
     }
 }
@@ -1011,4 +1366,6 @@ pub enum Session {
     Stop,
     Timeout,
     On,
+    /// 用户取消会话
+    Cancel,
 }
\ No newline at end of file
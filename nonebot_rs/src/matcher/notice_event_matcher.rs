@@ -129,22 +129,29 @@ impl Matcher<NoticeEvent> {
             // timeout 后调用，通知接受端 Timeout
             fn timeout_drop(&self, matcher: &Matcher<NoticeEvent>) {
                 let sender = matcher.bot.clone().unwrap().api_sender;
-                tokio::spawn(async move { sender.send(ApiChannelItem::TimeOut).await.unwrap() });
+                tokio::spawn(async move {
+                    if sender.send(ApiChannelItem::TimeOut).await.is_err() {
+                        event!(Level::DEBUG, "Temp Matcher receiver dropped before timeout");
+                    }
+                });
             }
             
-            fn match_(&mut self, _: &mut NoticeEvent) -> bool {
+            fn match_(&self, _: &mut NoticeEvent) -> bool {
                 true
             }
             
             async fn handle(&self, event: NoticeEvent, matcher: &mut Matcher<NoticeEvent>) {
-                matcher
+                let sent = matcher
                     .bot
                     .clone()
                     .unwrap()
                     .api_sender
                     .send(ApiChannelItem::NoticeEvent(event))
-                    .await
-                    .unwrap();
+                    .await;
+                // 等待方已结束（如 Handler 超时被取消）时忽略
+                if sent.is_err() {
+                    event!(Level::DEBUG, "Temp Matcher receiver dropped, event ignored");
+                }
             }
         }
         
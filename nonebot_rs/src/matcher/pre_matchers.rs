@@ -1,48 +1,92 @@
 use crate::config::BotConfig;
-use crate::event::MessageEvent;
+use crate::event::{GroupMessageEvent, MessageEvent, PrivateMessageEvent};
 use crate::matcher::PreMatcher;
 use crate::message::Message;
 use crate::utils::remove_space;
 use std::sync::Arc;
 
 
-/// 判定消息是否提及 bot（私聊，at，昵称）
-pub fn to_me() -> Arc<PreMatcher<MessageEvent>> {
-    let to_me = |e: &mut MessageEvent, config: BotConfig| -> bool {
-        match e {
-            MessageEvent::Private(_) => true,
-            MessageEvent::Group(g) => {
-                let bot_id = g.self_id.to_string();
-                let raw_message = remove_space(&g.raw_message);
-                for name in config.nicknames {
-                    if raw_message.starts_with(&name) {
-                        g.raw_message = remove_space(&raw_message[name.len()..]);
-                        return true;
-                    }
-                    if raw_message.ends_with(&name) {
-                        g.raw_message = remove_space(&raw_message.replace(&name, ""));
-                        return true;
-                    }
-                }
-                for message in &g.message {
-                    match message {
-                        Message::At { qq: qq_id, .. } => {
-                            if qq_id == &bot_id {
-                                g.raw_message = remove_space(
-                                    &raw_message.replace(&format!("[CQ:at,qq={}]", g.self_id), ""),
-                                );
-                                return true;
-                            }
-                        }
-                        _ => continue,
-                    }
+/// 可使用 [`to_me`] 与 [`command_start`] 的消息事件
+pub trait PreMatchMessage {
+    /// 群聊消息，私聊为 None
+    fn as_group_mut(&mut self) -> Option<&mut GroupMessageEvent>;
+    /// 可修改的字符串格式消息
+    fn raw_message_mut(&mut self) -> &mut String;
+}
+
+impl PreMatchMessage for MessageEvent {
+    fn as_group_mut(&mut self) -> Option<&mut GroupMessageEvent> {
+        match self {
+            MessageEvent::Private(_) => None,
+            MessageEvent::Group(g) => Some(g),
+        }
+    }
+
+    fn raw_message_mut(&mut self) -> &mut String {
+        match self {
+            MessageEvent::Private(p) => &mut p.raw_message,
+            MessageEvent::Group(g) => &mut g.raw_message,
+        }
+    }
+}
+
+impl PreMatchMessage for PrivateMessageEvent {
+    fn as_group_mut(&mut self) -> Option<&mut GroupMessageEvent> {
+        None
+    }
+
+    fn raw_message_mut(&mut self) -> &mut String {
+        &mut self.raw_message
+    }
+}
+
+impl PreMatchMessage for GroupMessageEvent {
+    fn as_group_mut(&mut self) -> Option<&mut GroupMessageEvent> {
+        Some(self)
+    }
+
+    fn raw_message_mut(&mut self) -> &mut String {
+        &mut self.raw_message
+    }
+}
+
+#[doc(hidden)]
+fn to_me_<E: PreMatchMessage>(e: &mut E, config: BotConfig) -> bool {
+    let g = match e.as_group_mut() {
+        Some(g) => g,
+        None => return true,
+    };
+    let bot_id = g.self_id.to_string();
+    let raw_message = remove_space(&g.raw_message);
+    for name in config.nicknames {
+        if raw_message.starts_with(&name) {
+            g.raw_message = remove_space(&raw_message[name.len()..]);
+            return true;
+        }
+        if raw_message.ends_with(&name) {
+            g.raw_message = remove_space(&raw_message.replace(&name, ""));
+            return true;
+        }
+    }
+    for message in &g.message {
+        match message {
+            Message::At { qq: qq_id, .. } => {
+                if qq_id == &bot_id {
+                    g.raw_message = remove_space(
+                        &raw_message.replace(&format!("[CQ:at,qq={}]", g.self_id), ""),
+                    );
+                    return true;
                 }
-                false
             }
+            _ => continue,
         }
-    };
+    }
+    false
+}
 
-    Arc::new(to_me)
+/// 判定消息是否提及 bot（私聊，at，昵称）
+pub fn to_me<E: PreMatchMessage>() -> Arc<PreMatcher<E>> {
+    Arc::new(to_me_::<E> as PreMatcher<E>)
 }
 
 pub fn is_superusers() -> Arc<PreMatcher<MessageEvent>> {
@@ -70,8 +114,8 @@ pub fn is_superusers() -> Arc<PreMatcher<MessageEvent>> {
 }
 
 #[doc(hidden)]
-fn command_start_(event: &mut MessageEvent, config: BotConfig) -> bool {
-    let raw_message = remove_space(&event.get_raw_message());
+fn command_start_<E: PreMatchMessage>(event: &mut E, config: BotConfig) -> bool {
+    let raw_message = remove_space(event.raw_message_mut());
     let command_starts = config.command_starts;
 
     if command_starts.is_empty() {
@@ -80,8 +124,7 @@ fn command_start_(event: &mut MessageEvent, config: BotConfig) -> bool {
 
     for sc in &command_starts {
         if raw_message.starts_with(sc) {
-            let new_raw_message = remove_space(&raw_message[sc.len()..]);
-            event.set_raw_message(new_raw_message);
+            *event.raw_message_mut() = remove_space(&raw_message[sc.len()..]);
             return true;
         }
     }
@@ -89,6 +132,6 @@ fn command_start_(event: &mut MessageEvent, config: BotConfig) -> bool {
 }
 
 /// 判定消息是否符合命令起始符
-pub fn command_start() -> Arc<PreMatcher<MessageEvent>> {
-    Arc::new(command_start_)
+pub fn command_start<E: PreMatchMessage>() -> Arc<PreMatcher<E>> {
+    Arc::new(command_start_::<E> as PreMatcher<E>)
 }
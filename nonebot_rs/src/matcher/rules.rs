@@ -90,3 +90,23 @@ pub fn on_command(msg: String) -> Rule<MessageEvent> {
     };
     Arc::new(on_command)
 }
+
+/// 判定消息是否匹配 EventArg 表达式，正则在构建 Rule 时编译，不合法时返回错误
+pub fn event_args(args: Vec<crate::matcher::EventArg>) -> Result<Rule<MessageEvent>, regex::Error> {
+    let args = crate::matcher::EventArgs::new(args)?;
+    let event_args = move |event: &MessageEvent, _: &BotConfig| -> bool {
+        args.is_match(event.get_raw_message())
+    };
+    Ok(Arc::new(event_args))
+}
+
+/// 判定 sender 身份不低于 level，群管理员与群主为 Admin
+pub fn permission<E>(level: crate::matcher::Visibility) -> Rule<E>
+where
+    E: crate::matcher::MessageContent + UserId,
+{
+    let permission = move |event: &E, config: &BotConfig| -> bool {
+        crate::matcher::Visibility::of_sender(event, config) >= level
+    };
+    Arc::new(permission)
+}
@@ -0,0 +1,114 @@
+use crate::event::{GroupId, MessageEvent, SelfId};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// 会话键，私聊 group_id 为 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    pub bot_id: i64,
+    pub group_id: i64,
+    pub user_id: i64,
+}
+
+impl SessionKey {
+    /// 由消息事件生成会话键，user_id 为 None 时使用发送者
+    pub fn new(event: &MessageEvent, user_id: Option<i64>) -> Self {
+        SessionKey {
+            bot_id: event.get_self_id(),
+            group_id: event.get_group_id(),
+            user_id: user_id.unwrap_or_else(|| event.get_sender_user_id()),
+        }
+    }
+}
+
+/// 会话注册表，记录处于会话中的 (bot, group, user) 及接管其消息的临时 Matcher
+///
+/// 会话期间该键的消息只会交给对应的临时 Matcher，发送 escape 命令可中止会话
+///
+/// ```toml
+/// [matcher.session]
+/// escapes = ["/cancel", "/退出"]
+/// escape_reply = "已退出当前会话"  # 为空则不回复
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionRegistry {
+    sessions: Arc<Mutex<HashMap<SessionKey, String>>>,
+    escapes: Arc<RwLock<Vec<String>>>,
+    escape_reply: Arc<RwLock<Option<String>>>,
+}
+
+impl Default for SessionRegistry {
+    fn default() -> Self {
+        SessionRegistry {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            escapes: Arc::new(RwLock::new(vec!["/cancel".to_string()])),
+            escape_reply: Arc::new(RwLock::new(Some("已退出当前会话".to_string()))),
+        }
+    }
+}
+
+impl SessionRegistry {
+    /// 以 matcher_name 占用会话，已被占用返回 false
+    pub fn claim(&self, key: SessionKey, matcher_name: &str) -> bool {
+        let mut sessions = self.sessions.lock().unwrap();
+        if sessions.contains_key(&key) {
+            return false;
+        }
+        sessions.insert(key, matcher_name.to_string());
+        true
+    }
+
+    /// 获取占用会话的 Matcher 名称
+    pub fn get(&self, key: &SessionKey) -> Option<String> {
+        self.sessions.lock().unwrap().get(key).cloned()
+    }
+
+    /// 会话是否被占用
+    pub fn is_active(&self, key: &SessionKey) -> bool {
+        self.sessions.lock().unwrap().contains_key(key)
+    }
+
+    /// 释放会话
+    pub fn release(&self, key: &SessionKey) -> Option<String> {
+        self.sessions.lock().unwrap().remove(key)
+    }
+
+    /// 仅当会话由 matcher_name 占用时释放，返回是否释放
+    pub fn release_owned(&self, key: &SessionKey, matcher_name: &str) -> bool {
+        let mut sessions = self.sessions.lock().unwrap();
+        if sessions.get(key).map(String::as_str) != Some(matcher_name) {
+            return false;
+        }
+        sessions.remove(key);
+        true
+    }
+
+    /// 释放 Matcher 占用的所有会话
+    pub fn release_matcher(&self, matcher_name: &str) {
+        self.sessions
+            .lock()
+            .unwrap()
+            .retain(|_, name| name != matcher_name);
+    }
+
+    /// 设置中止会话的命令
+    pub fn set_escapes(&self, escapes: Vec<String>) {
+        *self.escapes.write().unwrap() = escapes;
+    }
+
+    /// 设置中止会话时的回复，None 表示不回复
+    pub fn set_escape_reply(&self, reply: Option<String>) {
+        *self.escape_reply.write().unwrap() = reply;
+    }
+
+    /// 消息是否为中止会话命令
+    pub fn is_escape(&self, text: &str) -> bool {
+        let text = text.trim();
+        self.escapes.read().unwrap().iter().any(|e| e == text)
+    }
+
+    /// 中止会话时的回复
+    pub fn escape_reply(&self) -> Option<String> {
+        self.escape_reply.read().unwrap().clone()
+    }
+}
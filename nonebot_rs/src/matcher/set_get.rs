@@ -1,4 +1,4 @@
-use super::{Handler, Matcher, PreMatcher, Rule};
+use super::{Cooldown, CooldownScope, Handler, Matcher, MatcherMeta, PreMatcher, Rule, Visibility};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -23,6 +23,39 @@ where
         self.action_sender = Some(action_sender);
     }
 
+    /// 为 Matcher 添加会话注册表
+    /// 会在向 Matchers 添加时调用
+    pub fn set_sessions(&mut self, sessions: super::SessionRegistry) {
+        self.sessions = Some(sessions);
+    }
+
+    /// 为 Matcher 添加错误处理
+    /// 会在向 Matchers 添加时调用
+    pub fn set_error_hook(&mut self, error_hook: super::ErrorHook) {
+        self.error_hook = Some(error_hook);
+    }
+
+    /// 为 Matcher 添加运行期开关
+    /// 会在向 Matchers 添加时调用
+    pub fn set_switches(&mut self, switches: super::MatcherSwitches) {
+        self.switches = Some(switches);
+    }
+
+    /// 在群中（私聊 group_id 为 0）是否启用，临时 Matcher 不受运行期开关影响
+    pub fn is_enabled(&self, group_id: i64) -> bool {
+        match &self.switches {
+            Some(switches) if !self.temp => {
+                switches.is_enabled(&self.name, group_id, !self.disable)
+            }
+            _ => !self.disable,
+        }
+    }
+
+    /// 获取会话注册表
+    pub fn get_sessions(&self) -> Option<super::SessionRegistry> {
+        self.sessions.clone()
+    }
+
     /// 设置 priority
     pub fn set_priority(&mut self, priority: i8) -> Matcher<E> {
         self.priority = priority;
@@ -94,4 +127,104 @@ where
         self.timeout = Some(timeout);
         self.clone()
     }
+
+    /// 设置 Handler 并发上限
+    pub fn set_concurrency(&mut self, max: usize, overflow: super::Overflow) -> Matcher<E> {
+        self.concurrency = Some(super::ConcurrencyLimit::new(max, overflow));
+        self.clone()
+    }
+
+    /// 设置 Handler 最长运行秒数，超时的 Handler 将被取消并交由错误处理
+    pub fn set_handle_timeout(&mut self, seconds: u64) -> Matcher<E> {
+        self.handle_timeout = Some(seconds);
+        self.clone()
+    }
+
+    /// 设置冷却，seconds 为冷却时长（秒）
+    pub fn set_cooldown(&mut self, scope: CooldownScope, seconds: i64) -> Matcher<E> {
+        let reply = self.cooldown.as_ref().and_then(|c| c.reply.clone());
+        let mut cooldown = Cooldown::new(scope, seconds);
+        cooldown.reply = reply;
+        self.cooldown = Some(cooldown);
+        self.clone()
+    }
+
+    /// 设置冷却中的回复，需先调用 `set_cooldown`
+    ///
+    /// 回复中的 `{remaining}` 会被替换为剩余冷却秒数
+    pub fn set_cooldown_reply(&mut self, reply: &str) -> Matcher<E> {
+        if let Some(cooldown) = &mut self.cooldown {
+            cooldown.reply = Some(reply.to_string());
+        } else {
+            tracing::event!(
+                tracing::Level::WARN,
+                "Matcher {} set cooldown reply without cooldown",
+                self.name
+            );
+        }
+        self.clone()
+    }
+
+    /// 移除冷却
+    pub fn remove_cooldown(&mut self) -> Matcher<E> {
+        self.cooldown = None;
+        self.clone()
+    }
+
+    /// 获取冷却设置
+    pub fn get_cooldown(&self) -> Option<&Cooldown> {
+        self.cooldown.as_ref()
+    }
+
+    /// 设置元信息
+    pub fn set_meta(&mut self, meta: MatcherMeta) -> Matcher<E> {
+        self.meta = meta;
+        self.clone()
+    }
+
+    /// 添加命令名（首个为主命令名，其余视为别名）
+    pub fn add_command(&mut self, command: &str) -> Matcher<E> {
+        self.meta.commands.push(command.to_string());
+        self.clone()
+    }
+
+    /// 设置简介
+    pub fn set_description(&mut self, description: &str) -> Matcher<E> {
+        self.meta.description = Some(description.to_string());
+        self.clone()
+    }
+
+    /// 设置用法
+    pub fn set_usage(&mut self, usage: &str) -> Matcher<E> {
+        self.meta.usage = Some(usage.to_string());
+        self.clone()
+    }
+
+    /// 添加示例
+    pub fn add_example(&mut self, example: &str) -> Matcher<E> {
+        self.meta.examples.push(example.to_string());
+        self.clone()
+    }
+
+    /// 设置分类
+    pub fn set_category(&mut self, category: &str) -> Matcher<E> {
+        self.meta.category = Some(category.to_string());
+        self.clone()
+    }
+
+    /// 设置帮助中的可见性
+    pub fn set_visibility(&mut self, visibility: Visibility) -> Matcher<E> {
+        self.meta.visibility = visibility;
+        self.clone()
+    }
+
+    /// 获取 Matcher 概要信息
+    pub fn info(&self) -> super::MatcherInfo {
+        super::MatcherInfo {
+            name: self.name.clone(),
+            priority: self.priority,
+            disable: self.disable,
+            meta: self.meta.clone(),
+        }
+    }
 }
@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use tracing::{event, Level};
+
+/// 默认持久化文件
+pub const DEFAULT_SWITCH_PATH: &str = "matcher_switch.json";
+
+/// 开关作用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchScope {
+    /// 所有群与私聊
+    Global,
+    /// 指定群
+    Group(i64),
+}
+
+impl std::fmt::Display for SwitchScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SwitchScope::Global => write!(f, "全局"),
+            SwitchScope::Group(group_id) => write!(f, "群 {}", group_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SwitchState {
+    /// Matcher name -> 是否启用
+    #[serde(default)]
+    global: HashMap<String, bool>,
+    /// group_id -> Matcher name -> 是否启用
+    #[serde(default)]
+    groups: HashMap<i64, HashMap<String, bool>>,
+}
+
+/// Matcher 运行期开关，按群覆盖全局，全局覆盖 `Matcher.disable`
+///
+/// 修改后写入 JSON 文件，重启后自动恢复
+///
+/// ```toml
+/// [matcher.switch]
+/// path = "matcher_switch.json"
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MatcherSwitches {
+    state: Arc<RwLock<SwitchState>>,
+    path: Arc<RwLock<Option<PathBuf>>>,
+    /// 依次写入，保证文件内容为最后一次修改后的状态
+    saving: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl MatcherSwitches {
+    /// 设置持久化文件路径
+    pub fn set_path<P: Into<PathBuf>>(&self, path: P) {
+        *self.path.write().unwrap() = Some(path.into());
+    }
+
+    /// 从持久化文件恢复，未设置路径时使用默认路径
+    pub fn load(&self) {
+        let path = self
+            .path
+            .write()
+            .unwrap()
+            .get_or_insert_with(|| PathBuf::from(DEFAULT_SWITCH_PATH))
+            .clone();
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(_) => return,
+        };
+        match serde_json::from_str(&data) {
+            Ok(state) => *self.state.write().unwrap() = state,
+            Err(e) => event!(
+                Level::WARN,
+                "Failed to load matcher switches from {:?}: {}",
+                path,
+                e
+            ),
+        }
+    }
+
+    async fn save(&self) {
+        let path = match self.path.read().unwrap().clone() {
+            Some(path) => path,
+            None => return,
+        };
+        let _saving = self.saving.lock().await;
+        let data = serde_json::to_string_pretty(&*self.state.read().unwrap()).unwrap();
+        if let Err(e) = tokio::fs::write(&path, data).await {
+            event!(
+                Level::WARN,
+                "Failed to save matcher switches to {:?}: {}",
+                path,
+                e
+            );
+        }
+    }
+
+    /// 设置开关，None 表示清除该范围的设置，返回时已写入持久化文件
+    pub async fn set(&self, name: &str, scope: SwitchScope, enable: Option<bool>) {
+        {
+            let mut state = self.state.write().unwrap();
+            let switches = match scope {
+                SwitchScope::Global => &mut state.global,
+                SwitchScope::Group(group_id) => state.groups.entry(group_id).or_default(),
+            };
+            match enable {
+                Some(enable) => {
+                    switches.insert(name.to_string(), enable);
+                }
+                None => {
+                    switches.remove(name);
+                }
+            }
+            state.groups.retain(|_, switches| !switches.is_empty());
+        }
+        self.save().await;
+    }
+
+    /// 获取该范围的设置
+    pub fn get(&self, name: &str, scope: SwitchScope) -> Option<bool> {
+        let state = self.state.read().unwrap();
+        match scope {
+            SwitchScope::Global => state.global.get(name).copied(),
+            SwitchScope::Group(group_id) => state
+                .groups
+                .get(&group_id)
+                .and_then(|switches| switches.get(name))
+                .copied(),
+        }
+    }
+
+    /// Matcher 在群中（私聊 group_id 为 0）是否启用，均未设置时返回 default
+    pub fn is_enabled(&self, name: &str, group_id: i64, default: bool) -> bool {
+        if group_id != 0 {
+            if let Some(enable) = self.get(name, SwitchScope::Group(group_id)) {
+                return enable;
+            }
+        }
+        self.get(name, SwitchScope::Global).unwrap_or(default)
+    }
+}
+
+#[tokio::test]
+async fn switch_test() {
+    let path = std::env::temp_dir().join(format!("nbrs_switch_{}.json", std::process::id()));
+    let switches = MatcherSwitches::default();
+    switches.set_path(&path);
+    switches.set("echo", SwitchScope::Global, Some(false)).await;
+    switches.set("echo", SwitchScope::Group(1), Some(true)).await;
+    // 群设置覆盖全局设置，全局设置覆盖默认值
+    assert!(switches.is_enabled("echo", 1, false));
+    assert!(!switches.is_enabled("echo", 2, true));
+    assert!(!switches.is_enabled("echo", 0, true));
+    assert!(switches.is_enabled("help", 2, true));
+
+    let restored = MatcherSwitches::default();
+    restored.set_path(&path);
+    restored.load();
+    assert_eq!(restored.get("echo", SwitchScope::Group(1)), Some(true));
+    assert_eq!(restored.get("echo", SwitchScope::Global), Some(false));
+
+    switches.set("echo", SwitchScope::Group(1), None).await;
+    let restored = MatcherSwitches::default();
+    restored.set_path(&path);
+    restored.load();
+    assert_eq!(restored.get("echo", SwitchScope::Group(1)), None);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,240 @@
+use super::action::MatchersAction;
+use super::{rules, Handler, Matcher, TempMatcherGuard};
+use crate::event::{MessageEvent, NoticeEvent, RequestEvent, SelfId};
+use crate::NBError;
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::mpsc;
+use tracing::{event, Level};
+
+static WAIT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// `wait_for` 错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WaitError {
+    /// 超时未等到符合条件的事件
+    Timeout,
+    /// Matcher 未绑定 Bot 或 Matchers 已关闭
+    Closed,
+}
+
+impl std::fmt::Display for WaitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WaitError::Timeout => write!(f, "等待事件超时"),
+            WaitError::Closed => write!(f, "等待事件通道已关闭"),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {}
+
+impl From<WaitError> for NBError {
+    fn from(e: WaitError) -> Self {
+        match e {
+            WaitError::Timeout => NBError::State(super::Session::Timeout),
+            WaitError::Closed => NBError::State(super::Session::Stop),
+        }
+    }
+}
+
+/// 可被 `wait_for` 等待的事件类型
+pub trait WaitEvent: Clone + Send + Sync + SelfId + 'static {
+    /// 生成向 Matchers 添加该类型 Matcher 的 Action
+    fn add_action(matcher: Matcher<Self>) -> MatchersAction;
+}
+
+impl WaitEvent for MessageEvent {
+    fn add_action(matcher: Matcher<Self>) -> MatchersAction {
+        MatchersAction::AddMessageEventMatcher {
+            message_event_matcher: matcher,
+        }
+    }
+}
+
+impl WaitEvent for NoticeEvent {
+    fn add_action(matcher: Matcher<Self>) -> MatchersAction {
+        MatchersAction::AddNoticeEventMatcher {
+            notice_event_matcher: matcher,
+        }
+    }
+}
+
+impl WaitEvent for RequestEvent {
+    fn add_action(matcher: Matcher<Self>) -> MatchersAction {
+        MatchersAction::AddRequestEventMatcher {
+            request_event_matcher: matcher,
+        }
+    }
+}
+
+/// 临时 Matcher 的 Handler，将符合 filter 的事件转发至等待方
+struct WaitHandler<E, F> {
+    filter: F,
+    sender: mpsc::Sender<E>,
+}
+
+#[async_trait]
+impl<E, F> Handler<E> for WaitHandler<E, F>
+where
+    E: WaitEvent,
+    F: Fn(&E) -> bool + Send + Sync + 'static,
+{
+    fn match_(&self, event: &mut E) -> bool {
+        (self.filter)(event)
+    }
+
+    async fn handle(&self, event: E, _: &mut Matcher<E>) {
+        self.sender.send(event).await.ok();
+    }
+}
+
+impl<M> Matcher<M>
+where
+    M: Clone,
+{
+    /// 等待当前 Bot 收到下一个满足 filter 的 E 类型事件，timeout 单位为秒
+    ///
+    /// 调用时即完成临时 Matcher 注册，可在 await 前发送提示消息；等待不会阻止事件传递给其他 Matcher
+    ///
+    /// ``` rust,ignore
+    /// let notice: NoticeEvent = matcher
+    ///     .wait_for(|n: &NoticeEvent| n.notice_type == NoticeType::GroupIncrease, 60)
+    ///     .await?;
+    /// ```
+    pub fn wait_for<E, F>(
+        &self,
+        filter: F,
+        timeout: i64,
+    ) -> impl std::future::Future<Output = Result<E, WaitError>>
+    where
+        E: WaitEvent,
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        let registered = self.register_wait(filter, timeout);
+        async move {
+            // guard 在返回或被取消时移除临时 Matcher
+            let (_guard, mut receiver) = registered?;
+            let duration = std::time::Duration::from_secs(timeout.max(0) as u64);
+            match tokio::time::timeout(duration, receiver.recv()).await {
+                Ok(Some(event)) => Ok(event),
+                Ok(None) => Err(WaitError::Closed),
+                Err(_) => {
+                    event!(Level::DEBUG, "wait_for timeout");
+                    Err(WaitError::Timeout)
+                }
+            }
+        }
+    }
+
+    /// 注册转发事件的临时 Matcher
+    fn register_wait<E, F>(
+        &self,
+        filter: F,
+        timeout: i64,
+    ) -> Result<(TempMatcherGuard, mpsc::Receiver<E>), WaitError>
+    where
+        E: WaitEvent,
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        let (bot_id, action_sender) = match (&self.bot, &self.action_sender) {
+            (Some(bot), Some(action_sender)) => (bot.bot_id, action_sender.clone()),
+            _ => {
+                event!(Level::WARN, "wait_for with unbuilt matcher");
+                return Err(WaitError::Closed);
+            }
+        };
+        let (sender, receiver) = mpsc::channel::<E>(1);
+        let name = format!("wait-{}-{}", bot_id, WAIT_ID.fetch_add(1, Ordering::Relaxed));
+        let matcher = Matcher::new(&name, WaitHandler { filter, sender })
+            .add_rule(rules::is_bot(bot_id))
+            .set_priority(0)
+            .set_block(false)
+            .set_temp(true)
+            .set_timeout(crate::utils::timestamp() + timeout);
+        if action_sender.send(E::add_action(matcher)).is_err() {
+            return Err(WaitError::Closed);
+        }
+        Ok((TempMatcherGuard::new(&name, Some(action_sender)), receiver))
+    }
+}
+
+#[tokio::test]
+async fn wait_test() {
+    use crate::event::{Event, NoticeType};
+
+    struct Noop;
+
+    #[async_trait]
+    impl Handler<MessageEvent> for Noop {
+        fn match_(&self, _: &mut MessageEvent) -> bool {
+            true
+        }
+
+        async fn handle(&self, _: MessageEvent, _: &mut Matcher<MessageEvent>) {}
+    }
+
+    let (api_sender, _api_receiver) = mpsc::channel(1);
+    let (action_sender, _action_receiver) = mpsc::channel(1);
+    let (_resp_sender, resp_receiver) = tokio::sync::watch::channel(
+        serde_json::from_str(r#"{"status": "ok", "retcode": 0, "data": null, "echo": ""}"#)
+            .unwrap(),
+    );
+    let bot = crate::bot::Bot::new(
+        11,
+        crate::config::BotConfig::default(),
+        api_sender,
+        action_sender,
+        resp_receiver,
+    );
+    let event = |json: &str| -> Event { serde_json::from_str(json).unwrap() };
+    let recall = event(
+        r#"{"group_id":101,"message_id":111,"notice_type":"group_recall","operator_id":11,"post_type":"notice","self_id":11,"time":1631193409,"user_id":11}"#,
+    );
+    let poke = event(
+        r#"{"group_id":101,"notice_type":"notify","sub_type":"poke","post_type":"notice","self_id":11,"time":1631193409,"user_id":22,"target_id":11}"#,
+    );
+    let friend = event(
+        r#"{"post_type":"request","request_type":"friend","self_id":11,"time":1631193409,"user_id":22,"comment":"hi","flag":"f"}"#,
+    );
+
+    // 未绑定 Bot 的 Matcher 无法等待
+    let unbuilt = Matcher::new("unbuilt", Noop);
+    let result = unbuilt.wait_for(|_: &NoticeEvent| true, 5).await;
+    assert_eq!(result.unwrap_err(), WaitError::Closed);
+
+    let mut matchers = super::matchers::Matchers::new_empty();
+    let (sender, mut receiver) = super::action::channel();
+    let mut matcher = Matcher::new("waiter", Noop).build(bot.clone());
+    matcher.set_action_sender(sender);
+
+    // 仅满足 filter 的 NoticeEvent 交给等待方
+    let wait = matcher.wait_for(|n: &NoticeEvent| n.notice_type == NoticeType::Notify, 5);
+    let item = receiver.recv().await.unwrap();
+    matchers.handle_action_item(item);
+    matchers.dispatch(recall.clone(), &bot).await;
+    matchers.dispatch(poke, &bot).await;
+    let notice = wait.await.unwrap();
+    assert_eq!(notice.user_id, 22);
+    // 等待结束后移除临时 Matcher
+    let (action, _) = receiver.recv().await.unwrap();
+    assert!(!matchers.handle_action(action));
+    assert!(matchers.notice.values().all(|h| h.is_empty()));
+
+    // RequestEvent
+    let wait = matcher.wait_for(|r: &RequestEvent| r.user_id == 22, 5);
+    let item = receiver.recv().await.unwrap();
+    matchers.handle_action_item(item);
+    matchers.dispatch(friend, &bot).await;
+    assert_eq!(wait.await.unwrap().comment, "hi");
+    receiver.recv().await.unwrap();
+
+    // 超时返回 Timeout 并移除临时 Matcher
+    let wait = matcher.wait_for(|_: &NoticeEvent| false, 0);
+    let item = receiver.recv().await.unwrap();
+    matchers.handle_action_item(item);
+    assert_eq!(wait.await.unwrap_err(), WaitError::Timeout);
+    let (action, _) = receiver.recv().await.unwrap();
+    assert!(matchers.handle_action(action));
+    assert!(matchers.notice.values().all(|h| h.is_empty()));
+}
@@ -202,6 +202,35 @@ pub enum Message {
 }
 
 pub type MessageChain = Vec<Message>;
+
+/// 可转换为 MessageChain 的类型，文本转换为单个文本消息
+pub trait IntoMessageChain {
+    fn into_message_chain(self) -> MessageChain;
+}
+
+impl IntoMessageChain for MessageChain {
+    fn into_message_chain(self) -> MessageChain {
+        self
+    }
+}
+
+impl IntoMessageChain for Message {
+    fn into_message_chain(self) -> MessageChain {
+        vec![self]
+    }
+}
+
+impl IntoMessageChain for String {
+    fn into_message_chain(self) -> MessageChain {
+        vec![Message::text(self)]
+    }
+}
+
+impl IntoMessageChain for &str {
+    fn into_message_chain(self) -> MessageChain {
+        vec![Message::text(self)]
+    }
+}
 macro_rules! message_builder {
     ($fn_name: ident, $message_type: tt) => {
         pub fn $fn_name() -> Message {
@@ -76,7 +76,20 @@ impl Nonebot {
             "{}",
             "高性能自律実験4号機が稼働中····".red()
         );
+        self.config.set_loaded();
+        // 未设置 [store] 时保持默认的 memory 存储
+        if let Some(store) = &self.config.store {
+            match store.build() {
+                Ok(store) => crate::store::set_store(store),
+                Err(e) => tracing::event!(
+                    tracing::Level::ERROR,
+                    "Store build fail: {}, fallback to memory store",
+                    e
+                ),
+            }
+        }
         self.add_plugin(crate::logger::Logger);
+        self.load_registered_plugins().await;
         for (plugin_name, plugin) in &mut self.plugins {
             let plugin_config: Option<toml::Value> =
                 self.config.get_config(&plugin.plugin_name().to_lowercase());
@@ -92,6 +105,50 @@ impl Nonebot {
         }
     }
 
+    /// 加载链接进二进制的 `#[plugin]`
+    ///
+    /// 未手动添加 Matchers 或 Scheduler Plugin 时按需新建，并检查各 Plugin 的配置
+    async fn load_registered_plugins(&mut self) {
+        use colored::*;
+        let registered: Vec<_> = crate::plugin::registered_plugins().collect();
+        if registered.is_empty() {
+            return;
+        }
+        #[cfg(feature = "matcher")]
+        if registered.iter().any(|plugin| plugin.has_matchers())
+            && !self.plugins.contains_key(crate::matcher::matchers::PLUGIN_NAME)
+        {
+            self.add_plugin(crate::matcher::matchers::Matchers::new_empty());
+        }
+        #[cfg(feature = "scheduler")]
+        if registered.iter().any(|plugin| plugin.has_jobs())
+            && !self.plugins.contains_key("Scheduler")
+        {
+            self.add_plugin(crate::scheduler::Scheduler::new().await);
+        }
+        for plugin in self.plugins.values_mut() {
+            plugin.register(&registered);
+        }
+        for plugin in &registered {
+            if let Err(e) = plugin.check_config() {
+                tracing::event!(
+                    tracing::Level::WARN,
+                    "Plugin {} config [{}] is invalid: {}",
+                    plugin.name.red(),
+                    plugin.config_key(),
+                    e
+                );
+            }
+            tracing::event!(
+                tracing::Level::INFO,
+                "Plugin {} v{} is registered from {}",
+                plugin.name.red(),
+                plugin.version,
+                plugin.module_path
+            );
+        }
+    }
+
     /// Nonebot EventChannel receive handle
     async fn recv(mut self) {
         while let Some(action) = self.action_receiver.recv().await {
@@ -1,5 +1,7 @@
 // pub fn register_plugin(nb: crate::Nonebot) {}
 use async_trait::async_trait;
+#[cfg(feature = "scheduler")]
+use crate::scheduler::BoxedJob;
 
 
 /// A trait for nbrs plugins
@@ -12,4 +14,182 @@ pub trait Plugin: std::fmt::Debug {
     /// Load config
     #[allow(unused_variables)]
     async fn load_config(&mut self, config: toml::Value);
+    /// 注册 `#[plugin]` 声明的内容，在 load_config 之前调用
+    #[allow(unused_variables)]
+    fn register(&mut self, plugins: &[&'static PluginDef]) {}
+}
+
+/// 配置校验函数，参数为配置 key
+pub type ConfigCheck = fn(&str) -> Result<(), String>;
+
+/// `#[plugin]` 声明的 Plugin 单元
+///
+/// 链接进二进制的 PluginDef 在 Nonebot 启动时自动注册，
+/// Matcher 与中间件注册到 Matchers Plugin，定时任务注册到 Scheduler Plugin
+pub struct PluginDef {
+    /// Plugin 名称，同时为 `[plugin.<name>]` 设置与 PluginStore 的命名空间
+    pub name: &'static str,
+    pub version: &'static str,
+    pub description: &'static str,
+    /// 声明所在 module path，其下 handler 均属于该 Plugin
+    pub module_path: &'static str,
+    #[cfg(feature = "matcher")]
+    matchers: Option<fn(&mut crate::matcher::matchers::Matchers)>,
+    #[cfg(feature = "matcher")]
+    middlewares: Option<fn(&crate::matcher::Middlewares)>,
+    #[cfg(feature = "scheduler")]
+    jobs: Option<fn() -> Vec<BoxedJob>>,
+    config: Option<ConfigCheck>,
+}
+
+impl std::fmt::Debug for PluginDef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginDef")
+            .field("name", &self.name)
+            .field("version", &self.version)
+            .field("module_path", &self.module_path)
+            .finish()
+    }
+}
+
+impl PluginDef {
+    pub const fn new(
+        name: &'static str,
+        version: &'static str,
+        description: &'static str,
+        module_path: &'static str,
+    ) -> Self {
+        PluginDef {
+            name,
+            version,
+            description,
+            module_path,
+            #[cfg(feature = "matcher")]
+            matchers: None,
+            #[cfg(feature = "matcher")]
+            middlewares: None,
+            #[cfg(feature = "scheduler")]
+            jobs: None,
+            config: None,
+        }
+    }
+
+    /// 设置 Matcher 注册函数
+    #[cfg(feature = "matcher")]
+    pub const fn matchers(mut self, matchers: fn(&mut crate::matcher::matchers::Matchers)) -> Self {
+        self.matchers = Some(matchers);
+        self
+    }
+
+    /// 设置中间件注册函数
+    #[cfg(feature = "matcher")]
+    pub const fn middlewares(mut self, middlewares: fn(&crate::matcher::Middlewares)) -> Self {
+        self.middlewares = Some(middlewares);
+        self
+    }
+
+    /// 设置定时任务构建函数
+    #[cfg(feature = "scheduler")]
+    pub const fn jobs(mut self, jobs: fn() -> Vec<BoxedJob>) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// 设置配置校验函数，参数为配置 key
+    pub const fn config(mut self, config: ConfigCheck) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// 配置 key `plugin.<name>`
+    pub fn config_key(&self) -> String {
+        format!("plugin.{}", self.name)
+    }
+
+    /// 向 Matchers 注册该 Plugin 的 Matcher 与中间件
+    #[cfg(feature = "matcher")]
+    pub fn register_matchers(&self, matchers: &mut crate::matcher::matchers::Matchers) {
+        if let Some(register) = self.middlewares {
+            register(&matchers.get_middlewares());
+        }
+        if let Some(register) = self.matchers {
+            register(matchers);
+        }
+    }
+
+    #[cfg(feature = "matcher")]
+    pub fn has_matchers(&self) -> bool {
+        self.matchers.is_some() || self.middlewares.is_some()
+    }
+
+    /// 构建该 Plugin 的定时任务
+    #[cfg(feature = "scheduler")]
+    pub fn build_jobs(&self) -> Vec<BoxedJob> {
+        self.jobs.map(|jobs| jobs()).unwrap_or_default()
+    }
+
+    #[cfg(feature = "scheduler")]
+    pub fn has_jobs(&self) -> bool {
+        self.jobs.is_some()
+    }
+
+    /// 按声明的配置类型检查 `[plugin.<name>]`，未声明配置类型时总是通过
+    pub fn check_config(&self) -> Result<(), String> {
+        match self.config {
+            Some(check) => check(&self.config_key()),
+            None => Ok(()),
+        }
+    }
+}
+
+inventory::collect!(PluginDef);
+
+/// 所有链接进二进制的 `#[plugin]`
+pub fn registered_plugins() -> impl Iterator<Item = &'static PluginDef> {
+    inventory::iter::<PluginDef>.into_iter()
+}
+
+/// 查找 module path 所属的 `#[plugin]`，嵌套时取最内层
+pub fn find_plugin(module_path: &str) -> Option<&'static PluginDef> {
+    registered_plugins()
+        .filter(|plugin| {
+            module_path == plugin.module_path
+                || module_path
+                    .strip_prefix(plugin.module_path)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        })
+        .max_by_key(|plugin| plugin.module_path.len())
+}
+
+/// 以类型 T 解析已加载配置中的 key
+pub fn check_plugin_config<T>(key: &str) -> Result<(), String>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let config = crate::config::loaded_config().ok_or("配置未加载")?;
+    config
+        .get_full_config()
+        .get::<T>(key)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "matcher")]
+#[test]
+fn plugin_middlewares_test() {
+    struct Noop;
+
+    impl crate::matcher::Middleware for Noop {
+        fn name(&self) -> &str {
+            "noop"
+        }
+    }
+
+    let plugin = PluginDef::new("noop", "0.1.0", "", module_path!())
+        .middlewares(|middlewares| middlewares.add(Noop));
+    // 仅声明中间件的 Plugin 同样需要 Matchers Plugin
+    assert!(plugin.has_matchers());
+    let mut matchers = crate::matcher::matchers::Matchers::new_empty();
+    plugin.register_matchers(&mut matchers);
+    assert!(!matchers.get_middlewares().is_empty());
 }
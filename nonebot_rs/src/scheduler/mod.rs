@@ -1,26 +1,98 @@
 use crate::log::colored::*;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+mod schedule;
+
+pub use schedule::{parse_offset, CronTz, Schedule, ScheduleError};
 
 /// 定时任务 trait
 
 pub trait ScheduledJob {
     /// 定时任务标识
     fn name(&self) -> String;
-    fn cron(&self) -> String;
+    /// UTC cron 表达式，未覆盖 `schedule` 时使用
+    fn cron(&self) -> String {
+        String::new()
+    }
+    /// 调度方式，默认为 `cron` 返回的表达式
+    fn schedule(&self) -> Result<Schedule, ScheduleError> {
+        Schedule::cron(&self.cron())
+    }
+    /// 每次执行前随机延迟的上限
+    fn jitter(&self) -> Option<Duration> {
+        None
+    }
+    /// 最多执行次数
+    fn max_runs(&self) -> Option<u64> {
+        None
+    }
+    /// 执行任务的 Bot，默认为每个 Bot 各执行一次
+    fn target(&self) -> JobTarget {
+        JobTarget::EachBot
+    }
     fn call(&self, bot: Arc<crate::Bot>) -> std::pin::Pin<Box<dyn std::future::Future<Output=()> + Send + 'static>>;
 }
 
+/// 定时任务由哪个 Bot 执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobTarget {
+    /// 每个已连接的 Bot 各执行一次，Bot 断开时移除
+    EachBot,
+    /// 任选一个已连接的 Bot 执行一次，无 Bot 时跳过
+    AnyBot,
+    /// 由指定 bot_id 的 Bot 执行，未连接时跳过
+    Bot(i64),
+}
+
+impl std::fmt::Display for JobTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobTarget::EachBot => write!(f, "each bot"),
+            JobTarget::AnyBot => write!(f, "any bot"),
+            JobTarget::Bot(bot_id) => write!(f, "bot {}", bot_id),
+        }
+    }
+}
+
+/// 定时任务 trait object
+pub type BoxedJob = Box<dyn ScheduledJob + Send + Sync>;
+
 /// 定时任务执行器
 #[derive(Clone)]
 pub struct Scheduler {
     inner: tokio_cron_scheduler::JobScheduler,
-    bots: HashMap<i64, Vec<uuid::Uuid>>,
+    bots: Arc<Mutex<HashMap<i64, Vec<uuid::Uuid>>>>,
+    running: Arc<Mutex<HashMap<uuid::Uuid, Arc<RunningJob>>>>,
     tasks: Vec<Arc<Box<dyn ScheduledJob + Sync + Send + 'static>>>,
+    bot_getter: Option<crate::BotGetter>,
     config: SchedulerConfig,
 }
 
+/// 已运行的 Scheduler，用于运行时添加任务
+static RUNNING: once_cell::sync::OnceCell<Scheduler> = once_cell::sync::OnceCell::new();
+
+/// 调度中的定时任务
+struct RunningJob {
+    id: uuid::Uuid,
+    job: Arc<BoxedJob>,
+    /// 绑定的 Bot，为 None 时按 JobTarget 在执行时选取
+    bot: Option<Arc<crate::Bot>>,
+    target: JobTarget,
+    schedule: Schedule,
+    jitter: Option<Duration>,
+    max_runs: Option<u64>,
+    runs: AtomicU64,
+    /// 上一次计划执行时刻
+    last: Mutex<Option<DateTime<Utc>>>,
+    /// 当前等待中的 tokio_cron_scheduler one-shot Job
+    current: Mutex<Option<uuid::Uuid>>,
+    stopped: AtomicBool,
+}
+
 impl std::fmt::Debug for Scheduler {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Scheduler")
@@ -50,8 +122,10 @@ impl Scheduler {
     pub async fn new() -> Self {
         Self {
             inner: tokio_cron_scheduler::JobScheduler::new().await.expect("JobScheduler start failed"),
-            bots: HashMap::new(),
+            bots: Arc::new(Mutex::new(HashMap::new())),
+            running: Arc::new(Mutex::new(HashMap::new())),
             tasks: vec![],
+            bot_getter: None,
             config: SchedulerConfig { disable: false, jobs: HashMap::new() },
         }
     }
@@ -73,41 +147,50 @@ impl Scheduler {
         }
     }
     
-    async fn run(mut self, mut event_receiver: crate::EventReceiver) {
+    /// 检查所有定时任务的调度设置，存在无效设置时启动失败
+    fn check_tasks(&self) {
+        for task in &self.tasks {
+            if let Err(e) = task.schedule() {
+                panic!("Scheduler {} schedule is invalid: {}", task.name(), e);
+            }
+        }
+    }
+
+    async fn run(self, mut event_receiver: crate::EventReceiver) {
+        self.start().await;
+        for task in &self.tasks {
+            if task.target() != JobTarget::EachBot {
+                self.schedule_job(Arc::clone(task), None)
+                    .await
+                    .expect("Scheduler schedule job fail");
+            }
+        }
         while let Ok(event) = event_receiver.recv().await {
             match event {
                 crate::event::Event::Nonebot(bot) => {
                     match bot {
                         crate::event::NbEvent::BotConnect { bot } => {
                             let bot = Arc::new(bot);
-                            let mut vec = vec![];
                             for task in &self.tasks {
-                                let bot = Arc::clone(&bot);
-                                let _teak = Arc::clone(task);
-                                crate::log::event!(
-                                   crate::log::Level::INFO,
-                                   "Scheduler {} is Loaded",
-                                   _teak.name().blue()
-                               );
-                                let job = tokio_cron_scheduler::Job::new_async(_teak.cron().as_str(), move |_, _| {
-                                    _teak.call(bot.clone())
-                                }).unwrap();
-                                vec.push(job.guid());
-                                self.inner.add(job).await.expect("Scheduler add failed");
+                                if task.target() != JobTarget::EachBot {
+                                    continue;
+                                }
+                                if let Ok(id) = self.schedule_job(Arc::clone(task), Some(Arc::clone(&bot))).await {
+                                    self.bots.lock().unwrap().entry(bot.bot_id).or_default().push(id);
+                                }
                             }
-                            self.bots.insert(bot.bot_id, vec);
                         }
                         crate::event::NbEvent::BotDisconnect { bot } => {
-                            if let Some(uuid) = self.bots.get(&bot.bot_id) {
-                                for u in uuid {
-                                    self.inner.remove(u).await.expect("Scheduler remove failed");
+                            let ids = self.bots.lock().unwrap().remove(&bot.bot_id);
+                            if let Some(ids) = ids {
+                                for id in ids {
+                                    self.remove(id).await;
                                 }
                                 crate::log::event!(
                                    crate::log::Level::INFO,
                                    "Bot [{}] Scheduler created deleted",
                                    bot.bot_id.to_string().red(),
                                );
-                                self.bots.remove(&bot.bot_id);
                             }
                         }
                     }
@@ -115,16 +198,311 @@ impl Scheduler {
                 _ => {}
             }
         }
-        self.start().await;
+        self.shutdown().await;
     }
     async fn start(&self) {
         self.inner.start().await.unwrap();
+        crate::log::event!(
+            crate::log::Level::INFO,
+            "[{}] is started with {} jobs",
+            "Scheduler".red(),
+            self.tasks.len()
+        );
+    }
+
+    /// Nonebot 退出时停止所有任务
+    async fn shutdown(&self) {
+        let ids: Vec<uuid::Uuid> = self.running.lock().unwrap().keys().copied().collect();
+        for id in ids {
+            self.remove(id).await;
+        }
+        crate::log::event!(
+            crate::log::Level::INFO,
+            "[{}] is stopped",
+            "Scheduler".red()
+        );
+    }
+
+    /// 调度定时任务，返回任务 id，`bot` 为 None 时按 JobTarget 在执行时选取 Bot
+    async fn schedule_job(&self, job: Arc<BoxedJob>, bot: Option<Arc<crate::Bot>>) -> Result<uuid::Uuid, ScheduleError> {
+        let schedule = job.schedule().map_err(|e| {
+            crate::log::event!(
+                crate::log::Level::ERROR,
+                "Scheduler {} schedule is invalid: {}",
+                job.name().red(),
+                e
+            );
+            e
+        })?;
+        let running = Arc::new(RunningJob {
+            id: uuid::Uuid::new_v4(),
+            jitter: job.jitter(),
+            max_runs: job.max_runs(),
+            target: job.target(),
+            job,
+            bot,
+            schedule,
+            runs: AtomicU64::new(0),
+            last: Mutex::new(None),
+            current: Mutex::new(None),
+            stopped: AtomicBool::new(false),
+        });
+        let id = running.id;
+        crate::log::event!(
+            crate::log::Level::INFO,
+            "Scheduler {} is Loaded ({})",
+            running.job.name().blue(),
+            running.bot.as_ref().map_or(running.target, |bot| JobTarget::Bot(bot.bot_id))
+        );
+        self.running.lock().unwrap().insert(id, Arc::clone(&running));
+        self.schedule_next(running).await;
+        Ok(id)
+    }
+
+    /// 计算下一次执行时刻，并以 one-shot Job 等待
+    async fn schedule_next(&self, running: Arc<RunningJob>) {
+        if running.stopped.load(Ordering::SeqCst)
+            || running
+                .max_runs
+                .is_some_and(|max| running.runs.load(Ordering::SeqCst) >= max)
+        {
+            self.finish(&running, "finished");
+            return;
+        }
+        let now = Utc::now();
+        let last = *running.last.lock().unwrap();
+        let next = match running.schedule.next(now, last) {
+            Some(next) => next,
+            None => {
+                self.finish(&running, "finished");
+                return;
+            }
+        };
+        *running.last.lock().unwrap() = Some(next);
+        crate::log::event!(
+            crate::log::Level::DEBUG,
+            "Scheduler {} next run at {}",
+            running.job.name().blue(),
+            next
+        );
+        let mut delay = (next - now).to_std().unwrap_or_default();
+        if let Some(jitter) = running.jitter.filter(|jitter| !jitter.is_zero()) {
+            delay += jitter.mul_f64(rand::random::<f64>());
+        }
+        let scheduler = self.clone();
+        let fire = Arc::clone(&running);
+        let job = tokio_cron_scheduler::Job::new_one_shot_async(delay, move |_, _| {
+            scheduler.clone().fire(Arc::clone(&fire))
+        })
+        .expect("Scheduler create job failed");
+        *running.current.lock().unwrap() = Some(job.guid());
+        self.inner.add(job).await.expect("Scheduler add failed");
+    }
+
+    /// 执行一次任务并调度下一次
+    fn fire(self, running: Arc<RunningJob>) -> std::pin::Pin<Box<dyn std::future::Future<Output=()> + Send + 'static>> {
+        Box::pin(async move {
+            if running.stopped.load(Ordering::SeqCst) {
+                return;
+            }
+            match self.job_bot(&running) {
+                Some(bot) => {
+                    running.runs.fetch_add(1, Ordering::SeqCst);
+                    crate::log::event!(
+                        crate::log::Level::DEBUG,
+                        "Scheduler {} is running with Bot [{}]",
+                        running.job.name().blue(),
+                        bot.bot_id.to_string().red()
+                    );
+                    if let Err(e) = tokio::spawn(running.job.call(bot)).await {
+                        crate::log::event!(
+                            crate::log::Level::ERROR,
+                            "Scheduler {} failed: {}",
+                            running.job.name().red(),
+                            e
+                        );
+                    }
+                }
+                None => crate::log::event!(
+                    crate::log::Level::WARN,
+                    "Scheduler {} is skipped, no Bot available for {}",
+                    running.job.name().blue(),
+                    running.target
+                ),
+            }
+            self.schedule_next(running).await;
+        })
+    }
+
+    /// 选取执行任务的 Bot，AnyBot 时取 bot_id 最小的已连接 Bot
+    fn job_bot(&self, running: &RunningJob) -> Option<Arc<crate::Bot>> {
+        if let Some(bot) = &running.bot {
+            return Some(Arc::clone(bot));
+        }
+        let bots = self.bot_getter.as_ref()?.borrow();
+        let bot = match running.target {
+            JobTarget::Bot(bot_id) => bots.get(&bot_id),
+            _ => bots.values().min_by_key(|bot| bot.bot_id),
+        };
+        bot.map(|bot| Arc::new(bot.clone()))
+    }
+
+    fn finish(&self, running: &RunningJob, state: &str) {
+        if running.stopped.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.running.lock().unwrap().remove(&running.id);
+        if let Some(bot) = &running.bot {
+            if let Some(ids) = self.bots.lock().unwrap().get_mut(&bot.bot_id) {
+                ids.retain(|id| *id != running.id);
+            }
+        }
+        crate::log::event!(
+            crate::log::Level::INFO,
+            "Scheduler {} is {} after {} runs",
+            running.job.name().blue(),
+            state,
+            running.runs.load(Ordering::SeqCst)
+        );
+    }
+
+    /// 停止并移除任务，任务不存在时返回 false
+    async fn remove(&self, id: uuid::Uuid) -> bool {
+        let running = self.running.lock().unwrap().get(&id).cloned();
+        match running {
+            Some(running) => {
+                self.finish(&running, "removed");
+                let current = running.current.lock().unwrap().take();
+                if let Some(current) = current {
+                    self.inner.remove(&current).await.expect("Scheduler remove failed");
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 运行时以 Bot 调度定时任务，返回可用于 `remove_job` 的任务 id
+///
+/// 任务由 `bot` 执行并忽略 `target`，在 Bot 断开连接时移除，Scheduler Plugin 未运行时返回错误
+pub async fn add_job<J>(bot: crate::Bot, job: J) -> Result<uuid::Uuid, ScheduleError>
+where
+    J: ScheduledJob + Send + Sync + 'static,
+{
+    let scheduler = RUNNING
+        .get()
+        .ok_or_else(|| ScheduleError("Scheduler 未运行".to_string()))?;
+    let bot_id = bot.bot_id;
+    let id = scheduler
+        .schedule_job(Arc::new(Box::new(job)), Some(Arc::new(bot)))
+        .await?;
+    scheduler.bots.lock().unwrap().entry(bot_id).or_default().push(id);
+    Ok(id)
+}
+
+/// 移除运行时添加的定时任务，任务已结束或不存在时返回 false
+pub async fn remove_job(id: uuid::Uuid) -> bool {
+    match RUNNING.get() {
+        Some(scheduler) => scheduler.remove(id).await,
+        None => false,
+    }
+}
+
+/// 以闭包声明的定时任务，用于运行时添加
+///
+/// ```rust,ignore
+/// let job = Job::new("remind", Schedule::after(parse_duration("10m").unwrap()), |bot| async move {
+///     bot.send_private_msg(user_id, vec![Message::text("时间到了")]).await;
+/// });
+/// add_job(bot, job).await?;
+/// ```
+pub struct Job<F> {
+    name: String,
+    schedule: Schedule,
+    jitter: Option<Duration>,
+    max_runs: Option<u64>,
+    target: JobTarget,
+    run: F,
+}
+
+impl<F, Fut> Job<F>
+where
+    F: Fn(Arc<crate::Bot>) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    pub fn new(name: impl Into<String>, schedule: Schedule, run: F) -> Self {
+        Job {
+            name: name.into(),
+            schedule,
+            jitter: None,
+            max_runs: None,
+            target: JobTarget::EachBot,
+            run,
+        }
+    }
+
+    /// 每次执行前随机延迟的上限
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = Some(jitter);
+        self
+    }
+
+    /// 最多执行次数
+    pub fn max_runs(mut self, max_runs: u64) -> Self {
+        self.max_runs = Some(max_runs);
+        self
+    }
+
+    /// 执行任务的 Bot，仅在以 `Scheduler::add_task` 注册时生效
+    pub fn target(mut self, target: JobTarget) -> Self {
+        self.target = target;
+        self
+    }
+}
+
+impl<F, Fut> ScheduledJob for Job<F>
+where
+    F: Fn(Arc<crate::Bot>) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+    fn schedule(&self) -> Result<Schedule, ScheduleError> {
+        Ok(self.schedule.clone())
+    }
+    fn jitter(&self) -> Option<Duration> {
+        self.jitter
+    }
+    fn max_runs(&self) -> Option<u64> {
+        self.max_runs
+    }
+    fn target(&self) -> JobTarget {
+        self.target
+    }
+    fn call(&self, bot: Arc<crate::Bot>) -> std::pin::Pin<Box<dyn std::future::Future<Output=()> + Send + 'static>> {
+        Box::pin((self.run)(bot))
     }
 }
 
 pub trait ArcScheduledJob {
     fn name(&self) -> String;
-    fn cron(&self) -> String;
+    fn cron(&self) -> String {
+        String::new()
+    }
+    fn schedule(&self) -> Result<Schedule, ScheduleError> {
+        Schedule::cron(&self.cron())
+    }
+    fn jitter(&self) -> Option<Duration> {
+        None
+    }
+    fn max_runs(&self) -> Option<u64> {
+        None
+    }
+    fn target(&self) -> JobTarget {
+        JobTarget::EachBot
+    }
     fn call(self: &Arc<Self>, bot: Arc<crate::Bot>) -> std::pin::Pin<Box<dyn std::future::Future<Output=()> + Send + 'static>>;
 }
 
@@ -133,6 +511,18 @@ impl<T: ArcScheduledJob> ScheduledJob for Arc<T> {
     fn cron(&self) -> String {
         <T as ArcScheduledJob>::cron(&self)
     }
+    fn schedule(&self) -> Result<Schedule, ScheduleError> {
+        <T as ArcScheduledJob>::schedule(self)
+    }
+    fn jitter(&self) -> Option<Duration> {
+        <T as ArcScheduledJob>::jitter(self)
+    }
+    fn max_runs(&self) -> Option<u64> {
+        <T as ArcScheduledJob>::max_runs(self)
+    }
+    fn target(&self) -> JobTarget {
+        <T as ArcScheduledJob>::target(self)
+    }
     fn call(&self, bot: Arc<crate::Bot>) -> std::pin::Pin<Box<dyn std::future::Future<Output=()> + Send + 'static>> {
         <T as ArcScheduledJob>::call(&self, bot)
     }
@@ -141,9 +531,12 @@ impl<T: ArcScheduledJob> ScheduledJob for Arc<T> {
 
 #[async_trait::async_trait]
 impl crate::Plugin for Scheduler {
-    fn run(&self, event_receiver: crate::EventReceiver, _: crate::BotGetter) {
-        let scheduler = self.clone();
+    fn run(&self, event_receiver: crate::EventReceiver, bot_getter: crate::BotGetter) {
+        let mut scheduler = self.clone();
+        scheduler.bot_getter = Some(bot_getter);
         if !scheduler.config.disable {
+            scheduler.check_tasks();
+            let _ = RUNNING.set(scheduler.clone());
             tokio::spawn(scheduler.run(event_receiver));
         }
     }
@@ -152,6 +545,15 @@ impl crate::Plugin for Scheduler {
         "Scheduler"
     }
 
+    #[cfg(feature = "scheduler")]
+    fn register(&mut self, plugins: &[&'static crate::plugin::PluginDef]) {
+        for plugin in plugins {
+            for job in plugin.build_jobs() {
+                self.tasks.push(Arc::new(job));
+            }
+        }
+    }
+
     async fn load_config(&mut self, config: toml::Value) {
         self.config = config.try_into().expect("Scheduler load config fail");
         crate::log::event!(
@@ -0,0 +1,200 @@
+use chrono::{DateTime, FixedOffset, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// 定时任务的调度方式
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    /// cron 表达式，按给定时区的当地时间计算
+    Cron {
+        schedule: Box<cron::Schedule>,
+        tz: CronTz,
+    },
+    /// 每隔固定时长执行
+    Interval(Duration),
+    /// 在指定时刻执行一次
+    At(DateTime<Utc>),
+    /// 延迟指定时长后执行一次
+    Delay(Duration),
+}
+
+/// cron 表达式所在的时区
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CronTz {
+    /// 固定偏移，如 `+08:00`
+    Fixed(FixedOffset),
+    /// IANA 时区，如 `Asia/Shanghai`，按夏令时规则换算
+    Named(chrono_tz::Tz),
+}
+
+impl CronTz {
+    /// `time` 在该时区的当地时间
+    fn local(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            CronTz::Fixed(offset) => time.with_timezone(offset).naive_local(),
+            CronTz::Named(tz) => time.with_timezone(tz).naive_local(),
+        }
+    }
+
+    /// 当地时间对应的时刻
+    ///
+    /// 夏令时回拨重复的时间取较早的一次，夏令时跳过的时间按跳变前的偏移顺延
+    fn resolve(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        let tz = match self {
+            CronTz::Fixed(offset) => return Utc.from_utc_datetime(&(local - *offset)),
+            CronTz::Named(tz) => tz,
+        };
+        match tz.from_local_datetime(&local) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time.with_timezone(&Utc),
+            LocalResult::None => {
+                let before = tz
+                    .offset_from_utc_datetime(&(local - chrono::Duration::days(1)))
+                    .fix();
+                Utc.from_utc_datetime(&(local - before))
+            }
+        }
+    }
+}
+
+impl FromStr for CronTz {
+    type Err = ScheduleError;
+
+    /// 支持 `+08:00`、`+0800`、`-05`、`UTC` 与 IANA 时区名
+    fn from_str(tz: &str) -> Result<Self, Self::Err> {
+        if let Ok(offset) = parse_offset(tz) {
+            return Ok(CronTz::Fixed(offset));
+        }
+        tz.parse().map(CronTz::Named).map_err(|_| {
+            ScheduleError(format!(
+                "时区 {} 无效，应形如 +08:00、UTC 或 Asia/Shanghai",
+                tz
+            ))
+        })
+    }
+}
+
+/// Schedule 解析错误
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleError(pub String);
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+impl Schedule {
+    /// 按 UTC 计算的 cron 表达式
+    pub fn cron(expr: &str) -> Result<Self, ScheduleError> {
+        Self::cron_tz(expr, "UTC")
+    }
+
+    /// 按时区当地时间计算的 cron 表达式，时区形如 `+08:00`、`-05:30`、`UTC` 或 `Asia/Shanghai`
+    pub fn cron_tz(expr: &str, tz: &str) -> Result<Self, ScheduleError> {
+        let schedule = cron::Schedule::from_str(expr)
+            .map_err(|e| ScheduleError(format!("cron 表达式 {} 无效: {}", expr, e)))?;
+        Ok(Schedule::Cron {
+            schedule: Box::new(schedule),
+            tz: tz.parse()?,
+        })
+    }
+
+    /// 每隔 `interval` 执行，`interval` 不能为 0
+    pub fn every(interval: Duration) -> Result<Self, ScheduleError> {
+        if interval.is_zero() {
+            return Err(ScheduleError("执行间隔不能为 0".to_string()));
+        }
+        Ok(Schedule::Interval(interval))
+    }
+
+    /// 在 `time` 执行一次
+    pub fn at<Tz: TimeZone>(time: DateTime<Tz>) -> Self {
+        Schedule::At(time.with_timezone(&Utc))
+    }
+
+    /// 在 RFC 3339 格式的时刻执行一次，如 `2022-01-01T08:00:00+08:00`
+    pub fn at_str(time: &str) -> Result<Self, ScheduleError> {
+        DateTime::parse_from_rfc3339(time)
+            .map(Self::at)
+            .map_err(|e| ScheduleError(format!("时刻 {} 无效: {}", time, e)))
+    }
+
+    /// 延迟 `delay` 后执行一次
+    pub fn after(delay: Duration) -> Self {
+        Schedule::Delay(delay)
+    }
+
+    /// 下一次执行时刻，`last` 为上一次计划执行时刻，返回 None 时任务结束
+    ///
+    /// 错过的执行不会补齐，下一次执行时刻不早于 `now`
+    pub fn next(&self, now: DateTime<Utc>, last: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Cron { schedule, tz } => {
+                let after = last.map_or(now, |last| last.max(now));
+                // 在当地时间上匹配 cron，再换算回 UTC
+                schedule
+                    .after(&Utc.from_utc_datetime(&tz.local(after)))
+                    .map(|local| tz.resolve(local.naive_utc()))
+                    .find(|time| *time > after)
+            }
+            Schedule::Interval(interval) if interval.is_zero() => None,
+            Schedule::Interval(interval) => {
+                let interval = chrono::Duration::from_std(*interval).ok()?;
+                Some(last.map_or(now + interval, |last| (last + interval).max(now)))
+            }
+            Schedule::At(time) => match last {
+                None if *time >= now => Some(*time),
+                _ => None,
+            },
+            Schedule::Delay(delay) => match last {
+                None => Some(now + chrono::Duration::from_std(*delay).ok()?),
+                Some(_) => None,
+            },
+        }
+    }
+}
+
+/// 解析时区偏移，支持 `+08:00`、`+0800`、`-05` 与 `UTC`
+pub fn parse_offset(tz: &str) -> Result<FixedOffset, ScheduleError> {
+    let err = || ScheduleError(format!("时区 {} 无效，应形如 +08:00 或 UTC", tz));
+    if tz.eq_ignore_ascii_case("utc") || tz == "Z" {
+        return FixedOffset::east_opt(0).ok_or_else(err);
+    }
+    let (sign, rest) = match tz.as_bytes().first() {
+        Some(b'+') => (1, &tz[1..]),
+        Some(b'-') => (-1, &tz[1..]),
+        _ => return Err(err()),
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(err());
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (&digits[..], "0"),
+        4 => (&digits[..2], &digits[2..]),
+        _ => return Err(err()),
+    };
+    let hours: i32 = hours.parse().map_err(|_| err())?;
+    let minutes: i32 = minutes.parse().map_err(|_| err())?;
+    if hours > 23 || minutes > 59 {
+        return Err(err());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(err)
+}
+
+#[test]
+fn schedule_test() {
+    let time = |h, m| Utc.ymd(2022, 3, 13).and_hms(h, m, 0);
+    assert!(Schedule::every(Duration::ZERO).is_err());
+    let every = Schedule::every(Duration::from_secs(60)).unwrap();
+    assert_eq!(every.next(time(0, 10), Some(time(0, 0))), Some(time(0, 10)));
+    assert_eq!(every.next(time(0, 0), Some(time(0, 0))), Some(time(0, 1)));
+    // 2022-03-13 02:30 在纽约因夏令时不存在，顺延至 03:30 EDT
+    let cron = Schedule::cron_tz("0 30 2 * * *", "America/New_York").unwrap();
+    assert_eq!(cron.next(time(0, 0), None), Some(time(7, 30)));
+    let fixed = Schedule::cron_tz("0 0 8 * * *", "+08:00").unwrap();
+    assert_eq!(fixed.next(time(0, 0), None), Some(time(0, 0) + chrono::Duration::days(1)));
+    assert!(Schedule::cron_tz("0 0 8 * * *", "Mars/Base").is_err());
+}
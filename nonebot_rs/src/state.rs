@@ -0,0 +1,254 @@
+use crate::event::{GroupId, SelfId, UserId};
+use crate::NBResult;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::sync::Mutex;
+
+/// 状态作用域
+///
+/// 除 Global 外均按 Plugin 隔离，`#[event]` 中 Plugin 为 `ExtractContext::plugin_name`，
+/// 与 PluginStore 及 `[plugin.<name>]` 设置一致
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StateScope {
+    /// 全局共享，不同 Plugin 以相同类型访问同一状态，需要隔离时使用 newtype
+    Global,
+    /// 按 Plugin 隔离
+    Plugin(String),
+    /// 按 Plugin 与 Bot 隔离
+    Bot(String, i64),
+    /// 按 Plugin 与群隔离，同一群号在不同 Bot 间共享
+    Group(String, i64),
+    /// 按 Plugin 与用户隔离，同一用户在不同 Bot 间共享
+    User(String, i64),
+}
+
+/// 单个状态，各自持有锁
+pub type Shared<T> = Arc<Mutex<T>>;
+
+type StateMap = HashMap<(StateScope, TypeId), Arc<dyn Any + Send + Sync>>;
+
+/// 按作用域与类型存储的状态表
+///
+/// 每个 (作用域, 类型) 状态持有独立的锁，状态表本身只在查找时短暂加锁，
+/// 不同状态的 handler 可以并行运行
+///
+/// 状态创建后不会自动清除，按群或用户隔离的状态数量随群与用户增长，
+/// 不再需要时以 `remove` 或 `remove_scope` 清除
+#[derive(Clone, Default)]
+pub struct States {
+    inner: Arc<RwLock<StateMap>>,
+}
+
+impl std::fmt::Debug for States {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("States")
+            .field("len", &self.inner.read().unwrap().len())
+            .finish()
+    }
+}
+
+impl States {
+    /// 获取状态，不存在时由 f 创建
+    pub fn get_or_insert_with<T, F>(&self, scope: StateScope, f: F) -> Shared<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T,
+    {
+        let key = (scope, TypeId::of::<T>());
+        if let Some(state) = self.inner.read().unwrap().get(&key) {
+            return downcast(state.clone());
+        }
+        let mut inner = self.inner.write().unwrap();
+        let state = inner
+            .entry(key)
+            .or_insert_with(|| Arc::new(Mutex::new(f())) as Arc<dyn Any + Send + Sync>);
+        downcast(state.clone())
+    }
+
+    /// 获取状态，不存在时以 Default 创建
+    pub fn get<T>(&self, scope: StateScope) -> Shared<T>
+    where
+        T: Default + Send + 'static,
+    {
+        self.get_or_insert_with(scope, T::default)
+    }
+
+    /// 获取已存在的状态
+    pub fn try_get<T>(&self, scope: &StateScope) -> Option<Shared<T>>
+    where
+        T: Send + 'static,
+    {
+        let key = (scope.clone(), TypeId::of::<T>());
+        self.inner.read().unwrap().get(&key).cloned().map(downcast)
+    }
+
+    /// 替换状态，返回旧状态
+    pub fn insert<T>(&self, scope: StateScope, value: T) -> Option<Shared<T>>
+    where
+        T: Send + 'static,
+    {
+        let key = (scope, TypeId::of::<T>());
+        self.inner
+            .write()
+            .unwrap()
+            .insert(key, Arc::new(Mutex::new(value)))
+            .map(downcast)
+    }
+
+    /// 移除状态
+    pub fn remove<T>(&self, scope: &StateScope) -> Option<Shared<T>>
+    where
+        T: Send + 'static,
+    {
+        let key = (scope.clone(), TypeId::of::<T>());
+        self.inner.write().unwrap().remove(&key).map(downcast)
+    }
+
+    /// 移除作用域下所有类型的状态，返回移除数量
+    pub fn remove_scope(&self, scope: &StateScope) -> usize {
+        let mut inner = self.inner.write().unwrap();
+        let len = inner.len();
+        inner.retain(|(s, _), _| s != scope);
+        len - inner.len()
+    }
+}
+
+fn downcast<T>(state: Arc<dyn Any + Send + Sync>) -> Shared<T>
+where
+    T: Send + 'static,
+{
+    // 键中包含 TypeId，类型必然一致
+    state.downcast::<Mutex<T>>().unwrap()
+}
+
+static STATES: once_cell::sync::Lazy<States> = once_cell::sync::Lazy::new(States::default);
+
+/// 全局状态表
+pub fn states() -> &'static States {
+    &STATES
+}
+
+/// 可由事件确定作用域的状态，用于向 `#[event]` handler 注入
+pub trait ScopedState: Sized {
+    /// 根据事件与 Plugin 名称从全局状态表中获取，事件不属于该作用域时返回错误
+    fn from_event<E>(event: &E, plugin: &str) -> NBResult<Self>
+    where
+        E: SelfId + UserId + GroupId;
+}
+
+macro_rules! scoped_state {
+    ($(#[$doc:meta])* $name:ident, |$event:ident, $plugin:ident| $scope:expr) => {
+        $(#[$doc])*
+        #[derive(Debug)]
+        pub struct $name<T>(pub Shared<T>);
+
+        impl<T> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                $name(self.0.clone())
+            }
+        }
+
+        impl<T> std::ops::Deref for $name<T> {
+            type Target = Mutex<T>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<T> ScopedState for $name<T>
+        where
+            T: Default + Send + 'static,
+        {
+            #[allow(unused_variables)]
+            fn from_event<E>($event: &E, $plugin: &str) -> NBResult<Self>
+            where
+                E: SelfId + UserId + GroupId,
+            {
+                let scope: NBResult<StateScope> = $scope;
+                Ok($name(states().get::<T>(scope?)))
+            }
+        }
+    };
+}
+
+scoped_state!(
+    /// 全局状态
+    ///
+    /// ```rust,ignore
+    /// #[event]
+    /// async fn count(count: GlobalState<u64>, event: MessageEvent, matcher: &mut Matcher<MessageEvent>) -> NBResult<()> {
+    ///     let total = {
+    ///         let mut count = count.lock().await;
+    ///         *count += 1;
+    ///         *count
+    ///     };
+    ///     matcher.send_text(&total.to_string()).await;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// 所有 Plugin 共享，以同一类型访问同一状态，需要隔离时使用 newtype
+    GlobalState,
+    |event, plugin| Ok(StateScope::Global)
+);
+scoped_state!(
+    /// Plugin 状态
+    PluginState,
+    |event, plugin| Ok(StateScope::Plugin(plugin.to_string()))
+);
+scoped_state!(
+    /// 当前 Plugin 在当前 Bot 的状态
+    BotState,
+    |event, plugin| Ok(StateScope::Bot(plugin.to_string(), event.get_self_id()))
+);
+scoped_state!(
+    /// 当前 Plugin 在当前群的状态，非群聊事件提取失败
+    GroupState,
+    |event, plugin| match event.get_group_id() {
+        0 => Err("非群聊事件".into()),
+        group_id => Ok(StateScope::Group(plugin.to_string(), group_id)),
+    }
+);
+scoped_state!(
+    /// 当前 Plugin 对当前用户的状态
+    ///
+    /// 不会自动清除，状态数量随用户增长
+    UserState,
+    |event, plugin| Ok(StateScope::User(plugin.to_string(), event.get_user_id()))
+);
+
+#[tokio::test]
+async fn scoped_state_test() {
+    struct Event(i64);
+    impl SelfId for Event {
+        fn get_self_id(&self) -> i64 {
+            1
+        }
+    }
+    impl UserId for Event {
+        fn get_user_id(&self) -> i64 {
+            2
+        }
+    }
+    impl GroupId for Event {
+        fn get_group_id(&self) -> i64 {
+            self.0
+        }
+    }
+    #[derive(Default)]
+    struct Count(u32);
+
+    assert!(GroupState::<Count>::from_event(&Event(0), "a").is_err());
+    let a = UserState::<Count>::from_event(&Event(3), "a").unwrap();
+    a.lock().await.0 += 1;
+    // 不同 Plugin 的同类型状态互不影响
+    let b = UserState::<Count>::from_event(&Event(3), "b").unwrap();
+    assert_eq!(b.lock().await.0, 0);
+    let a = UserState::<Count>::from_event(&Event(4), "a").unwrap();
+    assert_eq!(a.lock().await.0, 1);
+    assert_eq!(states().remove_scope(&StateScope::User("a".to_string(), 2)), 1);
+    let a = UserState::<Count>::from_event(&Event(3), "a").unwrap();
+    assert_eq!(a.lock().await.0, 0);
+}
@@ -0,0 +1,86 @@
+use super::{now_millis, Entry, Store};
+use crate::NBResult;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// 内存存储，重启后丢失，用于测试或无需持久化的场景
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: RwLock<BTreeMap<String, Entry>>,
+}
+
+#[async_trait]
+impl Store for MemoryStore {
+    async fn get(&self, key: &str) -> NBResult<Option<serde_json::Value>> {
+        let now = now_millis();
+        Ok(self
+            .entries
+            .read()
+            .unwrap()
+            .get(key)
+            .filter(|entry| entry.is_alive(now))
+            .map(|entry| entry.value.clone()))
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+        ttl: Option<Duration>,
+    ) -> NBResult<()> {
+        self.entries
+            .write()
+            .unwrap()
+            .insert(key.to_string(), Entry::new(value, ttl));
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> NBResult<bool> {
+        let now = now_millis();
+        Ok(self
+            .entries
+            .write()
+            .unwrap()
+            .remove(key)
+            .is_some_and(|entry| entry.is_alive(now)))
+    }
+
+    async fn scan(&self, prefix: &str) -> NBResult<Vec<(String, serde_json::Value)>> {
+        let now = now_millis();
+        let mut entries = self.entries.write().unwrap();
+        entries.retain(|_, entry| entry.is_alive(now));
+        Ok(entries
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, entry)| (key.clone(), entry.value.clone()))
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn memory_store_test() {
+    use serde_json::json;
+    let store = MemoryStore::default();
+    store.set("alive", json!(1), Some(Duration::from_secs(3600))).await.unwrap();
+    store.set("expired", json!(2), Some(Duration::ZERO)).await.unwrap();
+    assert_eq!(store.get("alive").await.unwrap(), Some(json!(1)));
+    assert_eq!(store.get("expired").await.unwrap(), None);
+    // 已过期的键视为不存在
+    assert!(!store.delete("expired").await.unwrap());
+    assert!(store.delete("alive").await.unwrap());
+    assert!(!store.delete("alive").await.unwrap());
+
+    for key in ["a", "aa", "ab", "ab:1", "ac", "b"] {
+        store.set(key, json!(key), None).await.unwrap();
+    }
+    store.set("ab:2", json!(0), Some(Duration::ZERO)).await.unwrap();
+    let keys = |entries: Vec<(String, serde_json::Value)>| {
+        entries.into_iter().map(|(key, _)| key).collect::<Vec<_>>()
+    };
+    assert_eq!(keys(store.scan("ab").await.unwrap()), ["ab", "ab:1"]);
+    assert_eq!(keys(store.scan("a").await.unwrap()), ["a", "aa", "ab", "ab:1", "ac"]);
+    assert_eq!(keys(store.scan("").await.unwrap()).len(), 6);
+    assert!(store.scan("c").await.unwrap().is_empty());
+}
@@ -0,0 +1,267 @@
+use crate::{NBError, NBResult};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+mod memory;
+#[cfg(feature = "sled")]
+mod sled;
+
+pub use memory::MemoryStore;
+#[cfg(feature = "sled")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sled")))]
+pub use self::sled::SledStore;
+
+/// 键值存储后端
+///
+/// 值为 JSON，可设置过期时间，过期的键视为不存在
+#[async_trait]
+pub trait Store: Send + Sync {
+    async fn get(&self, key: &str) -> NBResult<Option<serde_json::Value>>;
+
+    /// 写入键值，ttl 为 None 时永不过期
+    async fn set(&self, key: &str, value: serde_json::Value, ttl: Option<Duration>)
+        -> NBResult<()>;
+
+    /// 删除键，返回键是否存在
+    async fn delete(&self, key: &str) -> NBResult<bool>;
+
+    /// 按前缀列出未过期的键值，按键排序
+    async fn scan(&self, prefix: &str) -> NBResult<Vec<(String, serde_json::Value)>>;
+}
+
+/// 后端中存储的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Entry {
+    pub value: serde_json::Value,
+    /// 过期时间，毫秒时间戳
+    pub expire: Option<u128>,
+}
+
+impl Entry {
+    pub fn new(value: serde_json::Value, ttl: Option<Duration>) -> Self {
+        Entry {
+            value,
+            expire: ttl.map(|ttl| now_millis() + ttl.as_millis()),
+        }
+    }
+
+    pub fn is_alive(&self, now: u128) -> bool {
+        match self.expire {
+            Some(expire) => expire > now,
+            None => true,
+        }
+    }
+}
+
+pub(crate) fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
+
+fn store_error<E: std::fmt::Display>(e: E) -> NBError {
+    NBError::Store(e.to_string())
+}
+
+/// 以 Plugin 名称为命名空间的存储，读写时进行序列化
+///
+/// ```rust,ignore
+/// let store = store("sign_in");
+/// let days: u32 = store.get(&user_id.to_string()).await?.unwrap_or_default();
+/// store.set(&user_id.to_string(), &(days + 1)).await?;
+/// ```
+#[derive(Clone)]
+pub struct PluginStore {
+    store: Arc<dyn Store>,
+    prefix: String,
+}
+
+impl std::fmt::Debug for PluginStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginStore")
+            .field("prefix", &self.prefix)
+            .finish()
+    }
+}
+
+impl PluginStore {
+    /// 键以命名空间长度为前缀，如 `7:weather:city`，不同命名空间的键不会互相冲突
+    pub fn new(store: Arc<dyn Store>, namespace: &str) -> Self {
+        PluginStore {
+            store,
+            prefix: format!("{}:{}:", namespace.len(), namespace),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+
+    pub async fn get<T>(&self, key: &str) -> NBResult<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        match self.store.get(&self.key(key)).await? {
+            Some(value) => serde_json::from_value(value).map(Some).map_err(store_error),
+            None => Ok(None),
+        }
+    }
+
+    /// 写入键值，永不过期
+    pub async fn set<T>(&self, key: &str, value: &T) -> NBResult<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = serde_json::to_value(value).map_err(store_error)?;
+        self.store.set(&self.key(key), value, None).await
+    }
+
+    /// 写入键值，ttl 后过期
+    pub async fn set_ex<T>(&self, key: &str, value: &T, ttl: Duration) -> NBResult<()>
+    where
+        T: Serialize + ?Sized,
+    {
+        let value = serde_json::to_value(value).map_err(store_error)?;
+        self.store.set(&self.key(key), value, Some(ttl)).await
+    }
+
+    /// 删除键，返回键是否存在
+    pub async fn delete(&self, key: &str) -> NBResult<bool> {
+        self.store.delete(&self.key(key)).await
+    }
+
+    /// 按前缀列出键值，返回的键不含命名空间
+    pub async fn scan<T>(&self, prefix: &str) -> NBResult<Vec<(String, T)>>
+    where
+        T: DeserializeOwned,
+    {
+        self.store
+            .scan(&self.key(prefix))
+            .await?
+            .into_iter()
+            .map(|(key, value)| {
+                let value = serde_json::from_value(value).map_err(store_error)?;
+                Ok((key[self.prefix.len()..].to_string(), value))
+            })
+            .collect()
+    }
+}
+
+/// 存储设置
+///
+/// 未设置 `[store]` 时使用 memory；设置后 backend 缺省为 sled（需启用 feature sled），否则为 memory
+///
+/// ```toml
+/// [store]
+/// backend = "sled"     # sled 或 memory
+/// path = "data/store"  # sled 数据目录
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoreConfig {
+    #[serde(default = "default_backend")]
+    pub backend: String,
+    #[serde(default = "default_path")]
+    pub path: String,
+}
+
+fn default_backend() -> String {
+    if cfg!(feature = "sled") {
+        "sled".to_string()
+    } else {
+        "memory".to_string()
+    }
+}
+
+fn default_path() -> String {
+    "data/store".to_string()
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        StoreConfig {
+            backend: "memory".to_string(),
+            path: default_path(),
+        }
+    }
+}
+
+impl StoreConfig {
+    /// 按设置构建存储后端，后端不可用或打开失败时返回错误
+    pub fn build(&self) -> NBResult<Arc<dyn Store>> {
+        match self.backend.as_str() {
+            "memory" => Ok(Arc::new(MemoryStore::default())),
+            #[cfg(feature = "sled")]
+            "sled" => SledStore::open(&self.path)
+                .map(|store| Arc::new(store) as Arc<dyn Store>)
+                .map_err(|e| NBError::Store(format!("failed to open {}: {}", self.path, e))),
+            #[cfg(not(feature = "sled"))]
+            "sled" => Err(NBError::Store(
+                "store backend sled requires feature sled".to_string(),
+            )),
+            backend => Err(NBError::Store(format!(
+                "unsupported store backend {}",
+                backend
+            ))),
+        }
+    }
+}
+
+static STORE: once_cell::sync::Lazy<RwLock<Arc<dyn Store>>> =
+    once_cell::sync::Lazy::new(|| RwLock::new(Arc::new(MemoryStore::default())));
+
+/// 替换全局存储后端，Nonebot 启动时按 `[store]` 设置调用
+pub fn set_store(store: Arc<dyn Store>) {
+    *STORE.write().unwrap() = store;
+}
+
+/// 获取全局存储后端
+pub fn global_store() -> Arc<dyn Store> {
+    STORE.read().unwrap().clone()
+}
+
+/// 获取 Plugin 命名空间下的存储，可在 handler 与定时任务中使用
+pub fn store(namespace: &str) -> PluginStore {
+    PluginStore::new(global_store(), namespace)
+}
+
+#[tokio::test]
+async fn plugin_store_test() {
+    let backend: Arc<dyn Store> = Arc::new(MemoryStore::default());
+    let a = PluginStore::new(backend.clone(), "a");
+    let ab = PluginStore::new(backend.clone(), "a:b");
+    a.set("b:c", &1).await.unwrap();
+    ab.set("c", &2).await.unwrap();
+    // 命名空间与键中的 `:` 不会造成冲突
+    assert_eq!(a.get::<i32>("b:c").await.unwrap(), Some(1));
+    assert_eq!(ab.get::<i32>("c").await.unwrap(), Some(2));
+    a.set("b:d", &3).await.unwrap();
+    a.set_ex("b:e", &4, Duration::ZERO).await.unwrap();
+    assert_eq!(
+        a.scan::<i32>("b:").await.unwrap(),
+        vec![("b:c".to_string(), 1), ("b:d".to_string(), 3)]
+    );
+    assert_eq!(ab.scan::<i32>("").await.unwrap(), vec![("c".to_string(), 2)]);
+    assert!(a.delete("b:c").await.unwrap());
+    assert_eq!(ab.get::<i32>("c").await.unwrap(), Some(2));
+}
+
+#[test]
+fn store_config_test() {
+    let default = StoreConfig::default();
+    assert_eq!(default.backend, "memory");
+    assert!(default.build().is_ok());
+    // 设置 [store] 但未指定 backend 时，启用 feature sled 则使用 sled
+    let config: StoreConfig = toml::from_str("path = \"data/store\"").unwrap();
+    assert_eq!(config.backend, default_backend());
+    let config: StoreConfig = toml::from_str("backend = \"redis\"").unwrap();
+    assert!(config.build().is_err());
+    #[cfg(not(feature = "sled"))]
+    {
+        let config: StoreConfig = toml::from_str("backend = \"sled\"").unwrap();
+        assert!(config.build().is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use super::{now_millis, store_error, Entry, Store};
+use crate::NBResult;
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// 基于 sled 的嵌入式文件存储
+#[derive(Debug, Clone)]
+pub struct SledStore {
+    db: sled::Db,
+}
+
+impl SledStore {
+    /// 打开或新建数据目录
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self, sled::Error> {
+        Ok(SledStore {
+            db: sled::open(path)?,
+        })
+    }
+
+    fn decode(bytes: &[u8]) -> NBResult<Entry> {
+        serde_json::from_slice(bytes).map_err(store_error)
+    }
+}
+
+#[async_trait]
+impl Store for SledStore {
+    async fn get(&self, key: &str) -> NBResult<Option<serde_json::Value>> {
+        let bytes = match self.db.get(key).map_err(store_error)? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+        let entry = Self::decode(&bytes)?;
+        if entry.is_alive(now_millis()) {
+            Ok(Some(entry.value))
+        } else {
+            // 仅在未被改写时删除过期条目
+            self.db
+                .compare_and_swap(key, Some(bytes), None as Option<&[u8]>)
+                .map_err(store_error)?
+                .ok();
+            Ok(None)
+        }
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        value: serde_json::Value,
+        ttl: Option<Duration>,
+    ) -> NBResult<()> {
+        let bytes = serde_json::to_vec(&Entry::new(value, ttl)).map_err(store_error)?;
+        self.db.insert(key, bytes).map_err(store_error)?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> NBResult<bool> {
+        match self.db.remove(key).map_err(store_error)? {
+            Some(bytes) => Ok(Self::decode(&bytes)?.is_alive(now_millis())),
+            None => Ok(false),
+        }
+    }
+
+    async fn scan(&self, prefix: &str) -> NBResult<Vec<(String, serde_json::Value)>> {
+        let now = now_millis();
+        let mut entries = vec![];
+        for item in self.db.scan_prefix(prefix) {
+            let (key, bytes) = item.map_err(store_error)?;
+            let entry = Self::decode(&bytes)?;
+            if entry.is_alive(now) {
+                entries.push((String::from_utf8_lossy(&key).to_string(), entry.value));
+            }
+        }
+        Ok(entries)
+    }
+}
@@ -11,3 +11,76 @@ pub fn timestamp() -> i64 {
     let time = Local::now();
     time.timestamp()
 }
+
+/// 解析 `90s`、`10m`、`1h30m`、`1d` 等形式的时长，纯数字视为秒
+///
+/// 为 `const fn`，`#[scheduler]` 借此在编译期检查时长
+pub const fn parse_duration(s: &str) -> Option<std::time::Duration> {
+    let bytes = s.as_bytes();
+    let (mut start, mut end) = (0, bytes.len());
+    while start < end && bytes[start].is_ascii_whitespace() {
+        start += 1;
+    }
+    while end > start && bytes[end - 1].is_ascii_whitespace() {
+        end -= 1;
+    }
+    if start == end {
+        return None;
+    }
+    let (mut total, mut num, mut digits) = (0u64, 0u64, 0);
+    let mut i = start;
+    while i < end {
+        let c = bytes[i];
+        i += 1;
+        if c.is_ascii_digit() {
+            num = match num.checked_mul(10) {
+                Some(n) => match n.checked_add((c - b'0') as u64) {
+                    Some(n) => n,
+                    None => return None,
+                },
+                None => return None,
+            };
+            digits += 1;
+            continue;
+        }
+        let unit = match c {
+            b's' => 1,
+            b'm' => 60,
+            b'h' => 60 * 60,
+            b'd' => 24 * 60 * 60,
+            _ => return None,
+        };
+        if digits == 0 {
+            return None;
+        }
+        total = match num.checked_mul(unit) {
+            Some(secs) => match total.checked_add(secs) {
+                Some(total) => total,
+                None => return None,
+            },
+            None => return None,
+        };
+        num = 0;
+        digits = 0;
+    }
+    if digits > 0 {
+        // 末尾不带单位的数字仅在整体为纯数字时视为秒
+        if end - start != digits {
+            return None;
+        }
+        total = num;
+    }
+    Some(std::time::Duration::from_secs(total))
+}
+
+#[test]
+fn parse_duration_test() {
+    use std::time::Duration;
+    assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+    assert_eq!(parse_duration(" 1h30m "), Some(Duration::from_secs(5400)));
+    assert_eq!(parse_duration("1d"), Some(Duration::from_secs(86400)));
+    assert_eq!(parse_duration("0s"), Some(Duration::ZERO));
+    for bad in ["", "m", "1m30", "5x", "-5m", "99999999999999999999s"] {
+        assert_eq!(parse_duration(bad), None, "{}", bad);
+    }
+}
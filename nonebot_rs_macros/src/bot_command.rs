@@ -44,19 +44,17 @@ pub(crate) fn parse_bot_command(
         let element_reg =
             regex::Regex::new(element_reg_str).expect("bot_command正则表达式编译失败");
         let mut bot_command_items = vec![];
-        let mut bot_command_item_strs = bot_command.split_whitespace();
         // 根据空格切分并循环
-        while let Some(item) = bot_command_item_strs.next() {
+        for item in bot_command.split_whitespace() {
             if !elements_reg.is_match(item) {
                 abort!(
                     &method.sig.ident.span(),
                     r#"bot_command中的元素必须是由固定字符串和参数组合而成, 例如 "/删除 {idx}" "请{min}分钟后提醒我{event}" "#
                 );
             }
-            let mut element_strs = element_reg.find_iter(item);
             let mut bot_command_elements = vec![];
             // 多此匹配
-            while let Some(element_str) = element_strs.next() {
+            for element_str in element_reg.find_iter(item) {
                 let element_str = element_str.as_str();
                 if element_str.starts_with('{') {
                     bot_command_elements.push(BotCommandRawTuple::Param(
@@ -96,12 +94,11 @@ pub(crate) fn parse_bot_args<'a>(
 ) -> Option<Vec<ParamsMather<'a>>> {
     if let Some(items) = items {
         let mut result = vec![];
-        let mut args_iter = args.iter();
         for item in items {
             result.push(match item {
                 BotCommandRaw::Command(tmp) => ParamsMather::Command(tmp),
                 BotCommandRaw::Param(tmp) => {
-                    let (pat, ty) = take_param(method, args_iter.next(), tmp.as_str());
+                    let (pat, ty) = take_param(method, args, tmp.as_str());
                     ParamsMather::Params(pat, ty)
                 }
                 BotCommandRaw::Multiple(multiple) => {
@@ -112,7 +109,7 @@ pub(crate) fn parse_bot_args<'a>(
                                 ParamsMatherTuple::Command(tmp.clone())
                             }
                             BotCommandRawTuple::Param(tmp) => {
-                                let (pat, ty) = take_param(method, args_iter.next(), tmp.as_str());
+                                let (pat, ty) = take_param(method, args, tmp.as_str());
                                 ParamsMatherTuple::Params(pat, ty)
                             }
                         })
@@ -127,46 +124,41 @@ pub(crate) fn parse_bot_args<'a>(
     }
 }
 
-fn take_param<'a>(method: &'a ItemFn, arg: Option<&&'a FnArg>, tmp: &str) -> (&'a Ident, &'a Type) {
-    if let Some(arg) = arg {
-        match arg {
-            FnArg::Receiver(_) => {
-                abort!(&method.sig.ident.span(), "bot_command的参数不支持Self");
+// 按名称查找命令参数，参数在方法中的位置不限
+fn take_param<'a>(method: &'a ItemFn, args: &'a [&'a FnArg], tmp: &str) -> (&'a Ident, &'a Type) {
+    for arg in args {
+        if let FnArg::Typed(t) = arg {
+            if let Pat::Ident(pi) = t.pat.deref() {
+                if pi.ident.to_string().eq(tmp) {
+                    return (&pi.ident, t.ty.deref());
+                }
             }
-            FnArg::Typed(t) => {
-                let pat = t.pat.deref();
-                match pat {
-                    Pat::Ident(pi) => {
-                        let ident = &pi.ident;
-                        if ident.to_string().eq(tmp) {
-                            let ty = t.ty.deref();
-                            return (ident, ty);
-                        } else {
-                            abort!(
-                                &method.sig.ident.span(),
-                                "bot_command中的参数名必须与参数名一致 {} != {}",
-                                tmp,
-                                pi.ident,
-                            );
-                        }
-                    }
-                    _ => {
-                        abort!(
-                            &method.sig.ident.span(),
-                            "bot_command中的参数必须是标识符 {}",
-                            tmp,
-                        );
+        }
+    }
+    abort!(
+        &method.sig.ident.span(),
+        "参数个数与bot_command不匹配，您的方法中缺少参数 : {}",
+        tmp,
+    );
+}
+
+// bot_command 中的全部参数名
+pub(crate) fn command_param_names(items: &Option<Vec<BotCommandRaw>>) -> Vec<String> {
+    let mut names = vec![];
+    for item in items.iter().flatten() {
+        match item {
+            BotCommandRaw::Command(_) => {}
+            BotCommandRaw::Param(name) => names.push(name.clone()),
+            BotCommandRaw::Multiple(multiple) => {
+                for m in multiple {
+                    if let BotCommandRawTuple::Param(name) = m {
+                        names.push(name.clone());
                     }
                 }
             }
         }
-    } else {
-        abort!(
-            &method.sig.ident.span(),
-            "参数个数与bot_command不匹配，您的方法中缺少参数 : {}",
-            tmp,
-        );
     }
+    names
 }
 
 #[cfg(test)]
@@ -6,6 +6,8 @@ use syn::Meta::{List, NameValue, Path};
 use syn::NestedMeta::{Lit, Meta};
 use syn::{AttributeArgs, ItemFn, NestedMeta};
 
+use crate::matcher_attr::MatcherAttrs;
+
 #[derive(Clone, Debug)]
 pub(crate) enum EventArg {
     All(Vec<EventArg>),
@@ -104,12 +106,51 @@ pub(crate) fn contains_bot_command(all: &Vec<EventArg>) -> bool {
     false
 }
 
+// 第一个需要匹配的 reg 表达式，用于提取捕获组
+pub(crate) fn first_reg(all: &[EventArg]) -> Option<String> {
+    for x in all {
+        match x {
+            EventArg::Reg(reg) => return Some(reg.clone()),
+            EventArg::All(args) => {
+                if let Some(reg) = first_reg(args) {
+                    return Some(reg);
+                }
+            }
+            EventArg::Not(_) | EventArg::BotCommand(_) => {}
+        }
+    }
+    None
+}
+
 pub(crate) fn parse_args_and_command(
     method: &ItemFn,
     attrs: AttributeArgs,
+    matcher_attrs: &mut MatcherAttrs,
 ) -> (Vec<EventArg>, Option<String>) {
+    // 先取出作用于 Matcher 本身的设置项，其只能直接写在event括号中
+    let mut args = vec![];
+    for nm in attrs {
+        match &nm {
+            Meta(NameValue(nv))
+                if nv.path.segments.len() == 1
+                    && MatcherAttrs::is_attr(&nv.path.segments.first().unwrap().ident.to_string()) =>
+            {
+                matcher_attrs.parse(nv);
+                continue;
+            }
+            Meta(Path(path))
+                if path.segments.len() == 1
+                    && MatcherAttrs::is_flag(&path.segments.first().unwrap().ident.to_string()) =>
+            {
+                matcher_attrs.parse_flag(&path.segments.first().unwrap().ident);
+                continue;
+            }
+            _ => {}
+        }
+        args.push(nm);
+    }
     // 从众多EventArg中找到bot_command（如果存在）
-    let all: Vec<EventArg> = parse_args(attrs);
+    let all: Vec<EventArg> = parse_args(args);
     let mut bot_command = None;
     let mut _all = vec![];
     for x in all {
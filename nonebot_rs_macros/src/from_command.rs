@@ -0,0 +1,406 @@
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::Lit::{Char, Str};
+use syn::Meta::{List, NameValue, Path};
+use syn::NestedMeta::Meta;
+use syn::{
+    Attribute, Data, DeriveInput, Expr, Field, Fields, GenericArgument, Ident, PathArguments,
+    Type,
+};
+
+/// 字段类型的形态
+enum FieldKind<'a> {
+    /// 必需值
+    Plain,
+    /// `Option<T>` 可选值
+    Optional(&'a Type),
+    /// `Vec<T>` 剩余所有参数
+    Rest(&'a Type),
+}
+
+/// 字段上的 #[command(..)]
+#[derive(Default)]
+struct FieldAttrs {
+    /// 参数名，默认为字段名
+    name: Option<String>,
+    /// 作为选项 `--long`
+    long: Option<String>,
+    /// 作为选项 `-s`
+    short: Option<char>,
+    /// 缺省值，`Some(None)` 为 `Default::default()`
+    default: Option<Option<Expr>>,
+    /// 校验函数 `fn(&T) -> Result<(), impl Display>`
+    validate: Option<syn::Path>,
+}
+
+fn command_metas(attrs: &[Attribute]) -> Vec<syn::NestedMeta> {
+    let mut metas = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("command")) {
+        match attr.parse_meta() {
+            Ok(List(list)) => metas.extend(list.nested),
+            _ => abort!(&attr.span(), "格式应为 #[command(..)]"),
+        }
+    }
+    metas
+}
+
+fn parse_field_attrs(field: &Field) -> FieldAttrs {
+    let mut field_attrs = FieldAttrs::default();
+    let field_name = field.ident.as_ref().unwrap().to_string();
+    for nm in command_metas(&field.attrs) {
+        match &nm {
+            Meta(Path(path)) if path.is_ident("long") => {
+                field_attrs.long = Some(field_name.replace('_', "-"))
+            }
+            Meta(Path(path)) if path.is_ident("default") => field_attrs.default = Some(None),
+            Meta(NameValue(nv)) if nv.path.segments.len() == 1 => {
+                let ident = &nv.path.segments.first().unwrap().ident;
+                match (ident.to_string().as_str(), &nv.lit) {
+                    ("name", Str(value)) => field_attrs.name = Some(value.value()),
+                    ("long", Str(value)) => field_attrs.long = Some(value.value()),
+                    ("short", Char(value)) => field_attrs.short = Some(value.value()),
+                    ("default", Str(value)) => match value.parse::<Expr>() {
+                        Ok(expr) => field_attrs.default = Some(Some(expr)),
+                        Err(_) => abort!(&value.span(), "default 不是合法的表达式"),
+                    },
+                    ("validate", Str(value)) => match value.parse::<syn::Path>() {
+                        Ok(path) => field_attrs.validate = Some(path),
+                        Err(_) => abort!(&value.span(), "validate 需为函数路径"),
+                    },
+                    ("short", _) => abort!(&ident.span(), "short只支持字符类型参数值"),
+                    ("name", _) | ("long", _) | ("default", _) | ("validate", _) => {
+                        abort!(&ident.span(), "{}只支持字符串类型参数值", ident)
+                    }
+                    _ => abort!(&ident.span(), "不支持的参数名称"),
+                }
+            }
+            _ => abort!(
+                &nm.span(),
+                "不支持的参数, 仅支持 name/long/short/default/validate"
+            ),
+        }
+    }
+    // 只设置 short 时同样作为选项
+    if field_attrs.short.is_some() && field_attrs.long.is_none() {
+        field_attrs.long = Some(field_name.replace('_', "-"));
+    }
+    field_attrs
+}
+
+/// 获取 `Option<T>` / `Vec<T>` 的形态
+fn field_kind(ty: &Type) -> FieldKind<'_> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => match path.path.segments.last() {
+            Some(segment) => segment,
+            None => return FieldKind::Plain,
+        },
+        _ => return FieldKind::Plain,
+    };
+    let inner = match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first() {
+            Some(GenericArgument::Type(inner)) => inner,
+            _ => return FieldKind::Plain,
+        },
+        _ => return FieldKind::Plain,
+    };
+    match segment.ident.to_string().as_str() {
+        "Option" => FieldKind::Optional(inner),
+        "Vec" => FieldKind::Rest(inner),
+        _ => FieldKind::Plain,
+    }
+}
+
+fn is_bool(ty: &Type) -> bool {
+    quote! {#ty}.to_string() == "bool"
+}
+
+fn expand_struct(input: &DeriveInput, fields: &Fields) -> TokenStream {
+    let fields = match fields {
+        Fields::Named(named) => &named.named,
+        _ => abort!(&input.ident.span(), "FromCommand 只支持具名字段结构体"),
+    };
+
+    let mut option_specs = vec![];
+    let mut positional_usage = vec![];
+    let mut option_usage = vec![];
+    let mut gets = quote! {};
+    let mut validates = quote! {};
+    let mut idents = vec![];
+    let mut rest_seen = false;
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let attrs = parse_field_attrs(field);
+        let kind = field_kind(ty);
+        let default = match &attrs.default {
+            Some(Some(expr)) => Some(quote! {#expr}),
+            Some(None) => Some(quote! {::std::default::Default::default()}),
+            None => None,
+        };
+        if default.is_some() && !matches!(kind, FieldKind::Plain) {
+            abort!(&field.span(), "Option/Vec 字段不需要设置 default");
+        }
+
+        match &attrs.long {
+            // 选项
+            Some(long) => {
+                if attrs.name.is_some() {
+                    abort!(&field.span(), "选项字段使用 long 设置名称");
+                }
+                let short = match attrs.short {
+                    Some(short) => quote! {Some(#short)},
+                    None => quote! {None},
+                };
+                let head = match attrs.short {
+                    Some(short) => format!("-{}|--{}", short, long),
+                    None => format!("--{}", long),
+                };
+                if is_bool(ty) {
+                    if default.is_some() {
+                        abort!(&field.span(), "开关选项不需要设置 default");
+                    }
+                    option_specs.push(quote! {::nonebot_rs::prelude::OptionSpec::flag(#long, #short)});
+                    option_usage.push(format!("[{}]", head));
+                    gets.extend(quote! {
+                        let #ident: #ty = __args.flag(#long);
+                    });
+                } else {
+                    option_specs.push(quote! {::nonebot_rs::prelude::OptionSpec::value(#long, #short)});
+                    match (&kind, default) {
+                        (FieldKind::Rest(_), _) => abort!(&field.span(), "选项不支持 Vec 类型"),
+                        (FieldKind::Optional(inner), _) => {
+                            option_usage.push(format!("[{} <{}>]", head, long));
+                            gets.extend(quote! {
+                                let #ident: #ty = __args.option::<#inner>(#long)?;
+                            });
+                        }
+                        (FieldKind::Plain, Some(default)) => {
+                            option_usage.push(format!("[{} <{}>]", head, long));
+                            gets.extend(quote! {
+                                let #ident: #ty = match __args.option::<#ty>(#long)? {
+                                    Some(value) => value,
+                                    None => #default,
+                                };
+                            });
+                        }
+                        (FieldKind::Plain, None) => {
+                            let missing = format!("--{}", long);
+                            option_usage.push(format!("{} <{}>", head, long));
+                            gets.extend(quote! {
+                                let #ident: #ty = match __args.option::<#ty>(#long)? {
+                                    Some(value) => value,
+                                    None => return Err(::nonebot_rs::prelude::CommandErrorKind::MissingArgument(#missing.to_string())),
+                                };
+                            });
+                        }
+                    }
+                }
+            }
+            // 位置参数，按字段声明顺序
+            None => {
+                let name = attrs.name.clone().unwrap_or_else(|| ident.to_string());
+                if rest_seen {
+                    abort!(&field.span(), "Vec 字段接收剩余所有参数，只能是最后一个位置参数");
+                }
+                match (&kind, default) {
+                    (FieldKind::Rest(inner), _) => {
+                        rest_seen = true;
+                        positional_usage.push(format!("[{}...]", name));
+                        gets.extend(quote! {
+                            let #ident: #ty = __args.rest::<#inner>(#name)?;
+                        });
+                    }
+                    (FieldKind::Optional(inner), _) => {
+                        positional_usage.push(format!("[{}]", name));
+                        gets.extend(quote! {
+                            let #ident: #ty = __args.next::<#inner>(#name)?;
+                        });
+                    }
+                    (FieldKind::Plain, Some(default)) => {
+                        positional_usage.push(format!("[{}]", name));
+                        gets.extend(quote! {
+                            let #ident: #ty = match __args.next::<#ty>(#name)? {
+                                Some(value) => value,
+                                None => #default,
+                            };
+                        });
+                    }
+                    (FieldKind::Plain, None) => {
+                        positional_usage.push(format!("<{}>", name));
+                        gets.extend(quote! {
+                            let #ident: #ty = __args.require::<#ty>(#name)?;
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(validate) = &attrs.validate {
+            let name = attrs
+                .long
+                .clone()
+                .map(|long| format!("--{}", long))
+                .or_else(|| attrs.name.clone())
+                .unwrap_or_else(|| ident.to_string());
+            validates.extend(quote! {
+                if let Err(reason) = #validate(&#ident) {
+                    return Err(::nonebot_rs::prelude::CommandErrorKind::Rejected {
+                        name: #name.to_string(),
+                        reason: reason.to_string(),
+                    });
+                }
+            });
+        }
+        idents.push(ident);
+    }
+    positional_usage.extend(option_usage);
+    let usage = positional_usage.join(" ");
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// 参数用法
+            pub fn usage() -> &'static str {
+                #usage
+            }
+
+            /// 从命令文本解析参数
+            pub fn parse_command(text: &str) -> Result<Self, ::nonebot_rs::prelude::CommandError> {
+                let parse = || -> Result<Self, ::nonebot_rs::prelude::CommandErrorKind> {
+                    let mut __args = ::nonebot_rs::prelude::CommandArgs::parse(text, &[#(#option_specs),*])?;
+                    #gets
+                    __args.finish()?;
+                    #validates
+                    Ok(Self { #(#idents),* })
+                };
+                parse().map_err(|kind| ::nonebot_rs::prelude::CommandError::new(kind, Self::usage().to_string()))
+            }
+        }
+
+        impl #impl_generics ::nonebot_rs::prelude::FromCommandMatcher for #ident #ty_generics #where_clause {
+            /// 消耗当前文本段中的所有参数
+            fn get(matcher: &mut ::nonebot_rs::prelude::CommandMatcher) -> Option<Self> {
+                let text = ::std::mem::take(&mut matcher.matching);
+                match Self::parse_command(&text) {
+                    Ok(value) => Some(value),
+                    Err(err) => {
+                        matcher.error = Some(err);
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn expand_enum(input: &DeriveInput, data: &syn::DataEnum) -> TokenStream {
+    if !input.generics.params.is_empty() {
+        abort!(&input.generics.span(), "FromCommand 枚举不支持泛型");
+    }
+    let mut choices = vec![];
+    let mut arms = quote! {};
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            abort!(&variant.span(), "FromCommand 枚举只支持无字段的变体");
+        }
+        // 默认以 snake_case 变体名作为可选值
+        let mut names = vec![to_snake_case(&variant.ident)];
+        for nm in command_metas(&variant.attrs) {
+            match &nm {
+                Meta(NameValue(nv)) if nv.path.is_ident("name") => match &nv.lit {
+                    Str(value) => names[0] = value.value(),
+                    _ => abort!(&nv.lit.span(), "name只支持字符串类型参数值"),
+                },
+                Meta(NameValue(nv)) if nv.path.is_ident("alias") => match &nv.lit {
+                    Str(value) => names.push(value.value()),
+                    _ => abort!(&nv.lit.span(), "alias只支持字符串类型参数值"),
+                },
+                _ => abort!(&nm.span(), "不支持的参数, 仅支持 name/alias"),
+            }
+        }
+        for name in &names {
+            if choices.contains(name) {
+                abort!(&variant.span(), "可选值 {} 重复", name);
+            }
+        }
+        let variant_ident = &variant.ident;
+        arms.extend(quote! {
+            if [#(#names),*].iter().any(|name| name.eq_ignore_ascii_case(s)) {
+                return Ok(Self::#variant_ident);
+            }
+        });
+        choices.push(names.swap_remove(0));
+    }
+
+    let ident = &input.ident;
+    let name = to_snake_case(ident);
+    let usage = format!("<{}>", choices.join("|"));
+    quote! {
+        impl #ident {
+            /// 可选值
+            pub const CHOICES: &'static [&'static str] = &[#(#choices),*];
+
+            /// 参数用法
+            pub fn usage() -> &'static str {
+                #usage
+            }
+        }
+
+        impl ::std::str::FromStr for #ident {
+            type Err = ::nonebot_rs::prelude::CommandErrorKind;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                #arms
+                Err(::nonebot_rs::prelude::CommandErrorKind::InvalidChoice {
+                    name: #name.to_string(),
+                    value: s.to_string(),
+                    choices: Self::CHOICES.iter().map(|choice| choice.to_string()).collect(),
+                })
+            }
+        }
+
+        impl ::nonebot_rs::prelude::FromCommandMatcher for #ident {
+            fn get(matcher: &mut ::nonebot_rs::prelude::CommandMatcher) -> Option<Self> {
+                let token = match <String as ::nonebot_rs::prelude::FromCommandMatcher>::get(matcher) {
+                    Some(token) => token,
+                    None => {
+                        matcher.error = Some(::nonebot_rs::prelude::CommandError::new(
+                            ::nonebot_rs::prelude::CommandErrorKind::MissingArgument(#name.to_string()),
+                            Self::usage().to_string(),
+                        ));
+                        return None;
+                    }
+                };
+                match token.parse::<Self>() {
+                    Ok(value) => Some(value),
+                    Err(kind) => {
+                        matcher.error = Some(::nonebot_rs::prelude::CommandError::new(kind, Self::usage().to_string()));
+                        None
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn to_snake_case(ident: &Ident) -> String {
+    let mut name = String::new();
+    for (idx, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() && idx > 0 {
+            name.push('_');
+        }
+        name.extend(c.to_lowercase());
+    }
+    name
+}
+
+pub(crate) fn expand(input: DeriveInput) -> TokenStream {
+    match &input.data {
+        Data::Struct(data) => expand_struct(&input, &data.fields),
+        Data::Enum(data) => expand_enum(&input, data),
+        Data::Union(_) => abort!(&input.ident.span(), "FromCommand 不支持 union"),
+    }
+}
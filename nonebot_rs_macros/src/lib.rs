@@ -1,32 +1,48 @@
 mod bot_command;
 mod event_arg;
+mod from_command;
+mod matcher_attr;
+mod plugin;
+mod scheduler_attr;
+mod send;
 mod utils;
 
 
-use proc_macro::{Ident, TokenStream};
+use proc_macro::TokenStream;
 
-use crate::bot_command::{parse_bot_args, parse_bot_command, ParamsMather, ParamsMatherTuple};
+use crate::bot_command::{
+    command_param_names, parse_bot_args, parse_bot_command, BotCommandRaw, ParamsMather,
+    ParamsMatherTuple,
+};
 
 use proc_macro_error::{abort, proc_macro_error};
-use quote::{quote, TokenStreamExt};
+use quote::{format_ident, quote, quote_spanned, TokenStreamExt};
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, FnArg, NestedMeta, Meta, Type, Pat};
-use syn::__private::TokenStream2;
-use syn::Lit::Str;
-use crate::event_arg::{args_to_token, parse_args_and_command};
-use crate::utils::{event_param_match, matcher_param_match, params_command};
+use syn::{parse_macro_input, FnArg};
+use crate::event_arg::{args_to_token, first_reg, parse_args_and_command};
+use crate::matcher_attr::MatcherAttrs;
+use crate::scheduler_attr::SchedulerAttrs;
+use crate::utils::{classify_params, HandlerParam};
 
 
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn event(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut matcher_attrs = MatcherAttrs::default();
+    let args: TokenStream = matcher_attrs.take_rules(args.into()).into();
     // 获取#[event]的参数
     let attrs = parse_macro_input!(args as syn::AttributeArgs);
     // 获取方法
     let method = parse_macro_input!(input as syn::ItemFn);
     // 解析参数
-    let (all, bot_command) = parse_args_and_command(&method, attrs);
+    let (all, bot_command) = parse_args_and_command(&method, attrs, &mut matcher_attrs);
     let command_items = parse_bot_command(&method, bot_command);
+    // bot_command 以固定字符串开头时作为命令名
+    if let Some(BotCommandRaw::Command(command)) = command_items.as_ref().and_then(|c| c.first()) {
+        matcher_attrs.commands.push(command.clone());
+    }
+    let aliases = matcher_attrs.aliases.clone();
+    let matcher_fns = matcher_attrs.handler_tokens();
     // 判断是否为async方法
     if method.sig.asyncness.is_none() {
         abort!(&method.sig.span(), "必须是async方法");
@@ -37,47 +53,90 @@ pub fn event(args: TokenStream, input: TokenStream) -> TokenStream {
         abort!(&sig_params.span(), "需要事件作为参数");
     }
     let params: Vec<_> = sig_params.iter().collect();
-    // 对状态,事件,匹配器进行匹配
-    let (state_param,
-        (event_param_pat, event_param_ty),
-        (matcher_param_pat, matcher_param_ty),
-        param_skip) = params_command(&params);
-    
-    let pms = parse_bot_args(&method, &params[param_skip..params.len()], command_items);
-    
+    // 对事件,匹配器,命令参数与提取参数进行分类
+    let command_names = command_param_names(&command_items);
+    let (event_param_ty, classified) = classify_params(&params, &command_names);
+
+    let pms = parse_bot_args(&method, &params, command_items);
+
     // 生成代码
     // gen token stream
     let ident = &method.sig.ident;
-    
-    // gen trait
     let block = &method.block;
-    let build = if all.is_empty() & pms.is_none() & state_param.is_none() {
+    let inputs = &method.sig.inputs;
+
+    // 传给 raw 的参数，与方法参数顺序一致
+    let mut extracts = quote! {};
+    let mut raw_args = vec![];
+    for (idx, (param, _, ty)) in classified.iter().enumerate() {
+        match param {
+            HandlerParam::Event => raw_args.push(quote! {__event}),
+            HandlerParam::Matcher => raw_args.push(quote! {__matcher}),
+            HandlerParam::Command(name) => raw_args.push(quote! {#name}),
+            HandlerParam::Extract => {
+                let arg = format_ident!("__arg{}", idx);
+                // 未实现 FromEvent 的参数在此处报错
+                extracts.append_all(quote_spanned! {ty.span()=>
+                    let #arg = match <#ty as ::nonebot_rs::prelude::FromEvent<#event_param_ty>>::from_event(&ctx).await {
+                        Ok(value) => value,
+                        Err(err) => {
+                            __matcher.report_error(err).await;
+                            return;
+                        }
+                    };
+                });
+                raw_args.push(quote! {#arg});
+            }
+        }
+    }
+    if !extracts.is_empty() {
+        let regex = match first_reg(&all) {
+            Some(reg) => quote! {
+                {
+                    static REGEX: ::std::sync::OnceLock<::nonebot_rs::regex::Regex> = ::std::sync::OnceLock::new();
+                    // 正则已在宏展开时校验
+                    Some(REGEX.get_or_init(|| ::nonebot_rs::regex::Regex::new(#reg).expect("event 正则表达式不正确")))
+                }
+            },
+            None => quote! {None},
+        };
+        extracts = quote! {
+            let ctx = ::nonebot_rs::prelude::ExtractContext::new(&__event, &*__matcher, module_path!(), #regex);
+            #extracts
+        };
+    }
+
+    // notice/request/meta 事件的类型过滤
+    let filter = matcher_attrs.filter_tokens(&quote! {#event_param_ty}.to_string());
+    let filter_event = if filter.is_empty() {
+        quote! {_}
+    } else {
+        quote! {event}
+    };
+
+    let build = if all.is_empty() & pms.is_none() {
         quote! {
             #[allow(non_camel_case_types)]
             pub struct #ident {}
             #[::nonebot_rs::async_trait]
             impl ::nonebot_rs::prelude::Handler<#event_param_ty> for #ident {
-                fn match_(&mut self, _: &mut #event_param_ty) -> bool {
+                fn match_(&self, #filter_event: &mut #event_param_ty) -> bool {
+                    #filter
                     true
                 }
-                async fn handle(&self, #event_param_pat: #event_param_ty,#matcher_param_pat: #matcher_param_ty) {
-                    if let Err(err) = self.raw(#event_param_pat,#matcher_param_pat).await {
-                        match err {
-                            ::nonebot_rs::NBError::Text(err_t) => {
-                                #matcher_param_pat.send_text(&err_t).await;
-                            }
-                            ::nonebot_rs::NBError::Other(err_m) => {
-                                #matcher_param_pat.send_(err_m).await;
-                            }
-                            _ => {}
-                        }
+                #matcher_fns
+                async fn handle(&self, __event: #event_param_ty, __matcher: &mut ::nonebot_rs::prelude::Matcher<#event_param_ty>) {
+                    #extracts
+                    if let Err(err) = self.raw(#(#raw_args),*).await {
+                        __matcher.report_error(err).await;
                     }
                 }
             }
             impl #ident {
-                async fn raw(&self, #event_param_pat: #event_param_ty,#matcher_param_pat: #matcher_param_ty) -> ::nonebot_rs::NBResult<()> #block
+                #[allow(clippy::too_many_arguments)]
+                async fn raw(&self, #inputs) -> ::nonebot_rs::NBResult<()> #block
             }
-            
+
         }
     } else {
         match quote! {#event_param_ty}.to_string().as_str() {
@@ -86,76 +145,24 @@ pub fn event(args: TokenStream, input: TokenStream) -> TokenStream {
             "PrivateMessageEvent" => (),
             _ => abort!(
                 &method.sig.span(),
-                "event 的参数只支持消息类型事件 [MessageEvent,PrivateMessageEvent,GroupMessageEvent]"
+                "reg 与 bot_command 只支持消息类型事件 [MessageEvent,PrivateMessageEvent,GroupMessageEvent]"
             ),
         }
         let args_vec = args_to_token(all);
 
         if pms.is_none() {
-            let mut ident_handle = quote! {};
-            let mut ident_raw = quote! {};
-            
-            match state_param {
-                None => {
-                    ident_handle.append_all(
-                        quote! {
-                            if let Err(err) = self.raw(#event_param_pat,#matcher_param_pat).await {
-                                match err {
-                                    ::nonebot_rs::NBError::Text(err_t) => {
-                                        #matcher_param_pat.send_text(&err_t).await;
-                                    }
-                                    ::nonebot_rs::NBError::Other(err_m) => {
-                                        #matcher_param_pat.send_(err_m).await;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                    );
-                    ident_raw.append_all(
-                        quote! {
-                            impl #ident {
-                                async fn raw(&self, #event_param_pat: #event_param_ty,#matcher_param_pat: #matcher_param_ty) -> ::nonebot_rs::NBResult<()> #block
-                            }
-                        }
-                    );
-                }
-                Some((state_pat, state_ty)) => {
-                    ident_handle.append_all(
-                        quote! {
-                            unsafe {
-                                let mut state = ::nonebot_rs::STATE.lock().await;
-                                if let Err(err) = self.raw(&mut state,#event_param_pat,#matcher_param_pat).await {
-                                    match err {
-                                        ::nonebot_rs::NBError::Text(err_t) => {
-                                            #matcher_param_pat.send_text(&err_t).await;
-                                        }
-                                        ::nonebot_rs::NBError::Other(err_m) => {
-                                            #matcher_param_pat.send_(err_m).await;
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                            
-                        }
-                    );
-                    ident_raw.append_all(
-                        quote! {
-                            impl #ident {
-                                async fn raw(&self,#state_pat:#state_ty, #event_param_pat: #event_param_ty,#matcher_param_pat: #matcher_param_ty) -> ::nonebot_rs::NBResult<()> #block
-                            }
-                        }
-                    );
-                }
-            }
             quote! {
                 #[allow(non_camel_case_types)]
                 pub struct #ident {}
                 #[::nonebot_rs::async_trait]
                 impl ::nonebot_rs::prelude::Handler<#event_param_ty> for #ident {
-                    fn match_(&mut self, event: &mut #event_param_ty) -> bool {
-                        if !::nonebot_rs::prelude::match_event_args_all(#args_vec, event.into()){
+                    fn match_(&self, event: &mut #event_param_ty) -> bool {
+                        static ARGS: ::std::sync::OnceLock<::nonebot_rs::prelude::EventArgs> = ::std::sync::OnceLock::new();
+                        let args = ARGS.get_or_init(|| {
+                            // 正则已在宏展开时校验
+                            ::nonebot_rs::prelude::EventArgs::new(#args_vec).expect("event 正则表达式不正确")
+                        });
+                        if !::nonebot_rs::prelude::match_event_args_all(args, event.into()){
                             return false;
                         }
                         let mut matcher = ::nonebot_rs::prelude::CommandMatcher::new(event.get_message_chain());
@@ -164,39 +171,99 @@ pub fn event(args: TokenStream, input: TokenStream) -> TokenStream {
                         }
                         true
                     }
-                    async fn handle(&self, #event_param_pat: #event_param_ty,#matcher_param_pat: #matcher_param_ty) {
-                        #ident_handle
+                    async fn handle(&self, __event: #event_param_ty, __matcher: &mut ::nonebot_rs::prelude::Matcher<#event_param_ty>) {
+                        #extracts
+                        if let Err(err) = self.raw(#(#raw_args),*).await {
+                            __matcher.report_error(err).await;
+                        }
                     }
+                    #matcher_fns
+                }
+                impl #ident {
+                    #[allow(clippy::too_many_arguments)]
+                    async fn raw(&self, #inputs) -> ::nonebot_rs::NBResult<()> #block
                 }
-                #ident_raw
             }
         } else {
             let mut p_pats = quote! {};
-            let mut command_params_in_raw = quote! {};
+            let mut p_tys = quote! {};
             let mut gets = quote! {};
-            for x in pms.expect("匹配出错") {
+            let pms = pms.expect("匹配出错");
+            // 完整命令用法，如 `/add <a> <b>`
+            let usage = pms
+                .iter()
+                .map(|x| match x {
+                    ParamsMather::Command(command) => command.trim().to_string(),
+                    ParamsMather::Params(pat, _) => format!("<{}>", pat),
+                    ParamsMather::Multiple(multiple) => multiple
+                        .iter()
+                        .map(|x| match x {
+                            ParamsMatherTuple::Command(name) => name.to_string(),
+                            ParamsMatherTuple::Params(pat, _) => format!("<{}>", pat),
+                        })
+                        .collect(),
+                })
+                .filter(|x| !x.is_empty())
+                .collect::<Vec<String>>()
+                .join(" ");
+            // 以命令名开头时，参数有误也视为匹配并回复用法
+            let (is_match, report_usage) = match pms.first() {
+                Some(ParamsMather::Command(command)) => {
+                    let command = command.trim();
+                    (
+                        quote! {
+                            match Self::parse_args(event) {
+                                Ok(_) => true,
+                                Err(err) => err.is_some(),
+                            }
+                        },
+                        quote! {
+                            Err(Some(mut err)) => {
+                                // 基础类型参数不附带用法，使用完整命令用法
+                                err.usage = if err.usage.is_empty() {
+                                    #usage.to_string()
+                                } else {
+                                    format!("{} {}", #command, err.usage)
+                                };
+                                __matcher.report_error(err.into()).await;
+                                return;
+                            }
+                        },
+                    )
+                }
+                _ => (quote! {Self::parse_args(event).is_ok()}, quote! {}),
+            };
+            for (idx, x) in pms.into_iter().enumerate() {
                 match x {
+                    ParamsMather::Command(command) if idx == 0 && !aliases.is_empty() => {
+                        gets.append_all(quote! {
+                            if !matcher.match_commands(&[#command, #(#aliases),*]) {
+                                return Err(None);
+                            }
+                        });
+                    }
                     ParamsMather::Command(command) => {
                         gets.append_all(quote! {
                             if !matcher.match_command(#command) {
-                                return false;
+                                return Err(None);
                             }
                         });
                     }
                     ParamsMather::Params(pat, ty) => {
                         p_pats.append_all(quote! {
-                           self.#pat.clone(),
+                           #pat,
                         });
-                        command_params_in_raw.append_all(quote! {
-                           #pat: #ty,
+                        p_tys.append_all(quote! {
+                            #ty,
                         });
 
+                        let name = pat.to_string();
                         gets.append_all(quote! {
+                            matcher.param = Some(#name);
                             let #pat: #ty = match ::nonebot_rs::prelude::matcher_get::<#ty>(&mut matcher) {
                                 Some(value) => value,
-                                None => return false,
+                                None => return Err(matcher.error.take()),
                             };
-                            self.#pat = #pat;
                         });
                     }
                     ParamsMather::Multiple(multiple) => {
@@ -221,129 +288,108 @@ pub fn event(args: TokenStream, input: TokenStream) -> TokenStream {
                             let mut ps = if let Some(ps) = matcher.tuple_matcher(vec![#mme]) {
                                 ps
                             } else {
-                                return false;
+                                return Err(None);
                             };
                             ps.reverse();
                         });
                         let len = pp.len();
                         gets.append_all(quote! {
                             if ps.len() != #len {
-                                return false;
+                                return Err(None);
                             }
                         });
                         for (pat, ty) in pp {
                             p_pats.append_all(quote! {
-                              self.#pat.clone(),
+                              #pat,
                             });
-                            command_params_in_raw.append_all(quote! {
-                                #pat: #ty,
+                            p_tys.append_all(quote! {
+                                #ty,
                             });
                             gets.append_all(quote! {
                                     let #pat: #ty = if let Some(np) = ps.pop() {
                                         let sub_matcher = ::nonebot_rs::prelude::TupleMatcher::new(np);
                                         match ::nonebot_rs::prelude::tuple_matcher_get::<#ty>(sub_matcher) {
                                             Some(value) => value,
-                                            None => return false,
+                                            None => return Err(None),
                                         }
                                     } else {
-                                        return false;
+                                        return Err(None);
                                     };
-                                    self.#pat = #pat;
                             });
                         }
                     }
                 }
             }
-            let mut ident_handle = quote! {};
-            let mut ident_raw = quote! {};
-            match state_param {
-                None => {
-                    ident_handle.append_all(
-                        quote! {
-                            if let Err(err) = self.raw(#event_param_pat,#matcher_param_pat,#p_pats).await {
-                                match err {
-                                    ::nonebot_rs::NBError::Text(err_t) => {
-                                        #matcher_param_pat.send_text(&err_t).await;
-                                    }
-                                    ::nonebot_rs::NBError::Other(err_m) => {
-                                        #matcher_param_pat.send_(err_m).await;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                    );
-                    ident_raw.append_all(
-                        quote! {
-                            impl #ident {
-                                async fn raw(&self, #event_param_pat: #event_param_ty,#matcher_param_pat: #matcher_param_ty, #command_params_in_raw) -> ::nonebot_rs::NBResult<()> #block
-                            }
-                        }
-                    );
-                }
-                Some((state_pat, state_ty)) => {
-                    ident_handle.append_all(
-                        quote! {
-                            unsafe {
-                                let mut state = ::nonebot_rs::STATE.lock().await;
-                                if let Err(err) = self.raw(&mut state,#event_param_pat,#matcher_param_pat,#p_pats).await {
-                                    match err {
-                                        ::nonebot_rs::NBError::Text(err_t) => {
-                                            #matcher_param_pat.send_text(&err_t).await;
-                                        }
-                                        ::nonebot_rs::NBError::Other(err_m) => {
-                                            #matcher_param_pat.send_(err_m).await;
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                            
-                        }
-                    );
-                    ident_raw.append_all(
-                        quote! {
-                            impl #ident {
-                                async fn raw(&self,#state_pat:#state_ty ,#event_param_pat: #event_param_ty,#matcher_param_pat: #matcher_param_ty, #command_params_in_raw) -> ::nonebot_rs::NBResult<()> #block
-                            }
-                        }
-                    );
-                }
-            }
             quote! {
                 #[allow(non_camel_case_types)]
                 #[derive(Default)]
-                pub struct #ident {
-                    #command_params_in_raw
-                }
+                pub struct #ident {}
 
-                #[::nonebot_rs::async_trait]
-                impl ::nonebot_rs::prelude::Handler<#event_param_ty> for #ident {
-                    fn match_(&mut self, event: &mut #event_param_ty) -> bool {
-                        if !::nonebot_rs::prelude::match_event_args_all(#args_vec, event.into()){
-                            return false;
-                        }
+                impl #ident {
+                    /// 从事件中解析命令参数，命令匹配但参数有误时返回附带用法的错误
+                    fn parse_args(event: &#event_param_ty) -> Result<(#p_tys), Option<::nonebot_rs::prelude::CommandError>> {
                         let mut matcher = ::nonebot_rs::prelude::CommandMatcher::new(event.get_message_chain());
                         #gets
                         if matcher.not_blank(){
+                            return Err(None);
+                        }
+                        Ok((#p_pats))
+                    }
+                }
+
+                #[::nonebot_rs::async_trait]
+                impl ::nonebot_rs::prelude::Handler<#event_param_ty> for #ident {
+                    fn match_(&self, event: &mut #event_param_ty) -> bool {
+                        static ARGS: ::std::sync::OnceLock<::nonebot_rs::prelude::EventArgs> = ::std::sync::OnceLock::new();
+                        let args = ARGS.get_or_init(|| {
+                            // 正则已在宏展开时校验
+                            ::nonebot_rs::prelude::EventArgs::new(#args_vec).expect("event 正则表达式不正确")
+                        });
+                        if !::nonebot_rs::prelude::match_event_args_all(args, event.into()){
                             return false;
                         }
-                        true
+                        #is_match
                     }
-                    async fn handle(&self, #event_param_pat: #event_param_ty,#matcher_param_pat: #matcher_param_ty) {
-                        #ident_handle
+                    async fn handle(&self, __event: #event_param_ty, __matcher: &mut ::nonebot_rs::prelude::Matcher<#event_param_ty>) {
+                        let (#p_pats) = match Self::parse_args(&__event) {
+                            Ok(args) => args,
+                            #report_usage
+                            Err(_) => return,
+                        };
+                        #extracts
+                        if let Err(err) = self.raw(#(#raw_args),*).await {
+                            __matcher.report_error(err).await;
+                        }
                     }
+                    #matcher_fns
 
                 }
-                #ident_raw
+                impl #ident {
+                    #[allow(clippy::too_many_arguments)]
+                    async fn raw(&self, #inputs) -> ::nonebot_rs::NBResult<()> #block
+                }
             }
         }
     };
+    let matcher_fn = matcher_attrs.matcher_tokens(ident, event_param_ty);
 
-    build.into()
+    quote! {
+        #build
+        #matcher_fn
+    }
+    .into()
 }
 
 
+/// 声明定时任务，方法名生成同名结构体，方法需为 `async fn(bot: Arc<Bot>)`
+///
+/// - `cron = "0 0 8 * * *"` cron 表达式，可配合 `tz = "+08:00"` 或 `tz = "Asia/Shanghai"` 设置时区，缺省为 UTC
+/// - `every = "30m"` 每隔固定时长执行，不能为 0
+/// - `at = "2022-01-01T08:00:00+08:00"` 在指定时刻执行一次
+/// - `delay = "10m"` 启动后延迟执行一次
+/// - `jitter = "30s"` 每次执行前随机延迟的上限
+/// - `max_runs = 3` 最多执行次数
+/// - `bot = "any"` 任选一个 Bot 执行，`bot = 123` 由指定 Bot 执行，缺省 `"each"` 为每个 Bot 各执行一次
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn scheduler(args: TokenStream, input: TokenStream) -> TokenStream {
@@ -369,32 +415,10 @@ pub fn scheduler(args: TokenStream, input: TokenStream) -> TokenStream {
         }
     };
     let block = &method.block;
-    let mut cron = String::new();
-    match attrs.first() {
-        None => { abort!(&method.span(), "必须要一个参数") }
-        Some(nm) => {
-            if let NestedMeta::Meta(meta) = nm {
-                if let Meta::NameValue(nv) = meta {
-                    if nv.path.segments.len() != 1 {
-                        abort!(&nv.path.span(), "表达式有且只能有一个片段");
-                    }
-                    let ident = &nv.path.segments.first().unwrap().ident;
-                    let ident_name = nv.path.segments.first().unwrap().ident.to_string();
-                    match ident_name.as_str() {
-                        "cron" => match &nv.lit {
-                            Str(value) => {
-                                cron.push_str(&value.value());
-                            }
-                            _ => abort!(&ident.span(), "cron只支持字符串类型参数值"),
-                        },
-                        _ => abort!(&ident.span(), "不支持的参数名称"),
-                    }
-                }
-            } else {
-                abort!(&nm.span(), "必须要一个参数")
-            }
-        }
-    }
+    let scheduler_attrs = SchedulerAttrs::parse(attrs, method.sig.span());
+    let cron = scheduler_attrs.cron.clone().unwrap_or_default();
+    let schedule_fns = scheduler_attrs.tokens();
+    let schedule_checks = scheduler_attrs.checks();
 
     let ident = method.sig.ident;
     let name = format!("{}", ident);
@@ -410,6 +434,8 @@ pub fn scheduler(args: TokenStream, input: TokenStream) -> TokenStream {
         #[derive(Clone)]
         pub struct #ident;
 
+        #schedule_checks
+
         impl ::nonebot_rs::prelude::ScheduledJob for #ident {
             fn name(&self) -> String {
                 #name.to_string()
@@ -417,6 +443,7 @@ pub fn scheduler(args: TokenStream, input: TokenStream) -> TokenStream {
             fn cron(&self) -> String {
                 #cron.to_string()
             }
+            #schedule_fns
             fn call(&self, #bot_params_pat: #bot_params_ty) -> std::pin::Pin<Box<dyn std::future::Future<Output=()> + Send + 'static>> {
                 let r = self.clone();
                 Box::pin(async move{
@@ -430,29 +457,63 @@ pub fn scheduler(args: TokenStream, input: TokenStream) -> TokenStream {
     ).into()
 }
 
+/// 声明主动发送的消息，方法名生成同名结构体
+///
+/// - `group = 123` 或 `user = 123` 设置发送目标
+/// - `template = "{name} 上线了"` 以参数渲染模板，此时方法体必须为空；
+///   未设置时方法体返回 String/Message/MessageChain 作为消息
+/// - `cron = "0 0 8 * * *"` 同时生成定时任务，由任一已连接的 Bot 发送一次，此时方法不能有参数
+///
+/// 生成 `render`、`send(&bot, ..)` 与 `send_any(&bots, ..)`
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn send(args: TokenStream, input: TokenStream) -> TokenStream {
-    // 获取#[event]的参数
+    // 获取#[send]的参数
     let attrs = parse_macro_input!(args as syn::AttributeArgs);
     // 获取方法
     let method = parse_macro_input!(input as syn::ItemFn);
-    // 解析参数
-    let (all, bot_command) = parse_args_and_command(&method, attrs);
-    let command_items = parse_bot_command(&method, bot_command);
-    
-    // 判断是否为async方法
-    if method.sig.asyncness.is_none() {
-        abort!(&method.sig.span(), "必须是async方法");
-    }
-    
-    // 判断事件
-    let sig_params = &method.sig.inputs;
-    if sig_params.is_empty() {
-        abort!(&sig_params.span(), "需要事件作为参数");
-    }
-    
-    
-    quote!().into()
+    send::expand(attrs, method).into()
 }
 
+/// 将内联 mod 声明为 Plugin，链接进二进制后由 Nonebot 启动时自动加载
+///
+/// - `name = "weather"` Plugin 名称，缺省为 mod 名
+/// - `version = "0.1.0"` 缺省为当前 crate 版本
+/// - `description = ".."` 缺省为 mod 的文档注释
+/// - `config = "WeatherConfig"` 启动时以该类型检查 `[plugin.<name>]`，类型路径在 mod 内解析，
+///   未在 mod 中声明或 use 的单段类型名从 mod 外（`super::`）解析
+/// - `matchers = [..]` / `jobs = [..]` 额外注册的 Matcher 与定时任务
+/// - `middlewares = [..]` 注册到 Matchers 的中间件，按声明顺序调用
+///
+/// mod 中的 `#[event]` 注册为 Matcher，`#[scheduler]` 与设置了 cron 的 `#[send]` 注册为定时任务
+#[proc_macro_error]
+#[proc_macro_attribute]
+pub fn plugin(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut plugin_attrs = plugin::PluginAttrs::default();
+    let args: TokenStream = plugin_attrs.take_lists(args.into()).into();
+    // 获取#[plugin]的参数
+    let attrs = parse_macro_input!(args as syn::AttributeArgs);
+    // 获取 mod
+    let module = parse_macro_input!(input as syn::ItemMod);
+    plugin::expand(plugin_attrs, attrs, module).into()
+}
+
+/// 为结构体或枚举生成 `FromCommandMatcher`，作为 bot_command 参数使用
+///
+/// 结构体按字段声明顺序解析位置参数，字段上的 `#[command(..)]`：
+/// - `long` / `long = "name"` / `short = 'n'` 作为选项，bool 字段为开关
+/// - `name = "city"` 设置位置参数在用法中的名称
+/// - `default` / `default = "7"` 缺少时的值
+/// - `validate = "path"` 校验函数 `fn(&T) -> Result<(), impl Display>`
+///
+/// `Option<T>` 为可选参数，`Vec<T>` 接收剩余所有位置参数
+///
+/// 枚举只支持无字段的变体，以 snake_case 变体名作为可选值（`HighPriority` 为 `high_priority`），变体上可设置 `name` 与 `alias`
+///
+/// 解析失败时回复错误原因与用法
+#[proc_macro_error]
+#[proc_macro_derive(FromCommand, attributes(command))]
+pub fn from_command(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    from_command::expand(input).into()
+}
@@ -0,0 +1,421 @@
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::Lit::{Bool, Int, Str};
+use syn::{Expr, MetaNameValue};
+
+use crate::utils::take_expr_list;
+
+/// #[event] 中作用于 Matcher 本身的设置项
+#[derive(Default, Debug)]
+pub(crate) struct MatcherAttrs {
+    pub(crate) cooldown: Option<i64>,
+    pub(crate) cooldown_scope: Option<String>,
+    pub(crate) cooldown_reply: Option<String>,
+    pub(crate) commands: Vec<String>,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) usage: Option<String>,
+    pub(crate) examples: Vec<String>,
+    pub(crate) category: Option<String>,
+    /// 帮助可见性，缺省时与 permission 一致
+    pub(crate) visibility: Option<String>,
+    /// 事件类型过滤，键为 notice_type|request_type|meta_event_type
+    pub(crate) event_types: Vec<(syn::Ident, syn::LitStr)>,
+    /// 事件子类型过滤
+    pub(crate) sub_types: Vec<(syn::Ident, syn::LitStr)>,
+    /// Matcher 名称，缺省为方法名
+    pub(crate) name: Option<String>,
+    pub(crate) priority: Option<i8>,
+    pub(crate) block: Option<bool>,
+    pub(crate) temp: Option<bool>,
+    pub(crate) to_me: bool,
+    pub(crate) command_start: bool,
+    pub(crate) permission: Option<String>,
+    /// 首个仅支持消息事件的设置项（to_me/command_start/permission），用于报错定位
+    pub(crate) message_only: Option<proc_macro2::Span>,
+    /// `rules = [...]` 中的 Rule 表达式
+    pub(crate) rules: Vec<Expr>,
+}
+
+impl MatcherAttrs {
+    /// 判断参数名是否为 Matcher 设置项
+    pub(crate) fn is_attr(name: &str) -> bool {
+        matches!(
+            name,
+            "cooldown"
+                | "cooldown_scope"
+                | "cooldown_reply"
+                | "alias"
+                | "description"
+                | "usage"
+                | "example"
+                | "category"
+                | "visibility"
+                | "notice_type"
+                | "request_type"
+                | "meta_event_type"
+                | "sub_type"
+                | "name"
+                | "priority"
+                | "block"
+                | "temp"
+                | "to_me"
+                | "command_start"
+                | "permission"
+        )
+    }
+
+    /// 判断参数名是否为可省略值的开关项，如 `#[event(to_me)]`
+    pub(crate) fn is_flag(name: &str) -> bool {
+        matches!(name, "to_me" | "command_start" | "block" | "temp")
+    }
+
+    pub(crate) fn parse_flag(&mut self, ident: &syn::Ident) {
+        if matches!(ident.to_string().as_str(), "to_me" | "command_start") {
+            self.message_only.get_or_insert(ident.span());
+        }
+        match ident.to_string().as_str() {
+            "to_me" => self.to_me = true,
+            "command_start" => self.command_start = true,
+            "block" => self.block = Some(true),
+            "temp" => self.temp = Some(true),
+            _ => abort!(&ident.span(), "不支持的参数名称"),
+        }
+    }
+
+    /// 取出 `rules = [...]`，其不是合法的 attribute 参数，需在解析前移除
+    pub(crate) fn take_rules(&mut self, args: TokenStream) -> TokenStream {
+        let (rest, rules) = take_expr_list(args, "rules", "Rule");
+        self.rules.extend(rules);
+        rest
+    }
+
+    pub(crate) fn parse(&mut self, nv: &MetaNameValue) {
+        let ident = &nv.path.segments.first().unwrap().ident;
+        let ident_name = ident.to_string();
+        match ident_name.as_str() {
+            "cooldown" => match &nv.lit {
+                Int(value) => match value.base10_parse::<i64>() {
+                    Ok(v) if v > 0 => self.cooldown = Some(v),
+                    _ => abort!(&nv.lit.span(), "cooldown必须是正整数"),
+                },
+                _ => abort!(&ident.span(), "cooldown只支持整数类型参数值"),
+            },
+            "cooldown_scope" => match &nv.lit {
+                Str(value) => {
+                    let v = value.value();
+                    match v.as_str() {
+                        "user" | "group" | "global" => self.cooldown_scope = Some(v),
+                        _ => abort!(&nv.lit.span(), "cooldown_scope只支持 user/group/global"),
+                    }
+                }
+                _ => abort!(&ident.span(), "cooldown_scope只支持字符串类型参数值"),
+            },
+            "cooldown_reply" => match &nv.lit {
+                Str(value) => self.cooldown_reply = Some(value.value()),
+                _ => abort!(&ident.span(), "cooldown_reply只支持字符串类型参数值"),
+            },
+            "alias" => match &nv.lit {
+                Str(value) => self.aliases.push(value.value()),
+                _ => abort!(&ident.span(), "alias只支持字符串类型参数值"),
+            },
+            "description" => match &nv.lit {
+                Str(value) => self.description = Some(value.value()),
+                _ => abort!(&ident.span(), "description只支持字符串类型参数值"),
+            },
+            "usage" => match &nv.lit {
+                Str(value) => self.usage = Some(value.value()),
+                _ => abort!(&ident.span(), "usage只支持字符串类型参数值"),
+            },
+            "example" => match &nv.lit {
+                Str(value) => self.examples.push(value.value()),
+                _ => abort!(&ident.span(), "example只支持字符串类型参数值"),
+            },
+            "category" => match &nv.lit {
+                Str(value) => self.category = Some(value.value()),
+                _ => abort!(&ident.span(), "category只支持字符串类型参数值"),
+            },
+            "visibility" => match &nv.lit {
+                Str(value) => {
+                    let v = value.value();
+                    match v.as_str() {
+                        "public" | "admin" | "superuser" | "hidden" => self.visibility = Some(v),
+                        _ => abort!(
+                            &nv.lit.span(),
+                            "visibility只支持 public/admin/superuser/hidden"
+                        ),
+                    }
+                }
+                _ => abort!(&ident.span(), "visibility只支持字符串类型参数值"),
+            },
+            "notice_type" | "request_type" | "meta_event_type" => match &nv.lit {
+                Str(value) => self.event_types.push((ident.clone(), value.clone())),
+                _ => abort!(&ident.span(), "{}只支持字符串类型参数值", ident_name),
+            },
+            "sub_type" => match &nv.lit {
+                Str(value) => self.sub_types.push((ident.clone(), value.clone())),
+                _ => abort!(&ident.span(), "sub_type只支持字符串类型参数值"),
+            },
+            "name" => match &nv.lit {
+                Str(value) => self.name = Some(value.value()),
+                _ => abort!(&ident.span(), "name只支持字符串类型参数值"),
+            },
+            "priority" => match &nv.lit {
+                Int(value) => match value.base10_parse::<i8>() {
+                    Ok(v) => self.priority = Some(v),
+                    _ => abort!(&nv.lit.span(), "priority必须是 i8 范围内的整数"),
+                },
+                _ => abort!(&ident.span(), "priority只支持整数类型参数值"),
+            },
+            "block" | "temp" | "to_me" | "command_start" => match &nv.lit {
+                Bool(value) => match ident_name.as_str() {
+                    "to_me" | "command_start" if value.value => self.parse_flag(ident),
+                    "block" => self.block = Some(value.value),
+                    "temp" => self.temp = Some(value.value),
+                    "to_me" => self.to_me = value.value,
+                    _ => self.command_start = value.value,
+                },
+                _ => abort!(&ident.span(), "{}只支持布尔类型参数值", ident_name),
+            },
+            "permission" => match &nv.lit {
+                Str(value) => {
+                    let v = value.value();
+                    match v.as_str() {
+                        "admin" | "superuser" => {
+                            self.message_only.get_or_insert(ident.span());
+                            self.permission = Some(v)
+                        }
+                        _ => abort!(&nv.lit.span(), "permission只支持 admin/superuser"),
+                    }
+                }
+                _ => abort!(&ident.span(), "permission只支持字符串类型参数值"),
+            },
+            _ => abort!(&ident.span(), "不支持的参数名称"),
+        }
+    }
+
+    /// 生成构建 Matcher 的方法
+    pub(crate) fn matcher_tokens(
+        &self,
+        ident: &syn::Ident,
+        event_ty: &syn::Type,
+    ) -> proc_macro2::TokenStream {
+        let name = self.name.clone().unwrap_or_else(|| ident.to_string());
+        let is_message_event = matches!(
+            quote! {#event_ty}.to_string().as_str(),
+            "MessageEvent" | "PrivateMessageEvent" | "GroupMessageEvent"
+        );
+        if let (Some(span), false) = (self.message_only, is_message_event) {
+            abort!(
+                span,
+                "to_me/command_start/permission 只支持消息类型事件 [MessageEvent,PrivateMessageEvent,GroupMessageEvent]"
+            );
+        }
+        let mut settings = quote! {};
+        if let Some(priority) = self.priority {
+            settings.extend(quote! {.set_priority(#priority)});
+        }
+        if let Some(block) = self.block {
+            settings.extend(quote! {.set_block(#block)});
+        }
+        if let Some(temp) = self.temp {
+            settings.extend(quote! {.set_temp(#temp)});
+        }
+        if self.to_me {
+            settings.extend(quote! {.add_pre_matcher(::nonebot_rs::prelude::pre_matchers::to_me())});
+        }
+        if self.command_start {
+            settings.extend(
+                quote! {.add_pre_matcher(::nonebot_rs::prelude::pre_matchers::command_start())},
+            );
+        }
+        if let Some(permission) = &self.permission {
+            let level = match permission.as_str() {
+                "superuser" => quote! {::nonebot_rs::prelude::Visibility::SuperUser},
+                _ => quote! {::nonebot_rs::prelude::Visibility::Admin},
+            };
+            settings.extend(quote! {.add_rule(::nonebot_rs::prelude::rules::permission(#level))});
+        }
+        for rule in &self.rules {
+            settings.extend(quote! {.add_rule(#rule)});
+        }
+        quote! {
+            impl #ident {
+                /// 构建 Matcher
+                pub fn matcher() -> ::nonebot_rs::prelude::Matcher<#event_ty> {
+                    ::nonebot_rs::prelude::Matcher::new(#name, #ident {})#settings
+                }
+            }
+        }
+    }
+
+    /// 生成 match_ 中的事件类型过滤，同名参数可重复，满足其一即可
+    pub(crate) fn filter_tokens(&self, event_ty: &str) -> proc_macro2::TokenStream {
+        let first_key = match self.event_types.first().or_else(|| self.sub_types.first()) {
+            Some((key, _)) => key.span(),
+            None => return quote! {},
+        };
+        let (type_key, type_names, sub_type_names) = match event_ty {
+            "NoticeEvent" => ("notice_type", Some(NOTICE_TYPES), Some(NOTICE_SUB_TYPES)),
+            "RequestEvent" => ("request_type", Some(REQUEST_TYPES), Some(REQUEST_SUB_TYPES)),
+            "MetaEvent" => ("meta_event_type", None, None),
+            _ => abort!(
+                first_key,
+                "notice_type/request_type/meta_event_type/sub_type 只支持 [NoticeEvent,RequestEvent,MetaEvent]"
+            ),
+        };
+        let mut types = vec![];
+        for (key, value) in &self.event_types {
+            if key != type_key {
+                abort!(key.span(), "{} 事件不支持 {}", event_ty, key);
+            }
+            check_name(value, type_key, type_names);
+            types.push(value);
+        }
+        let mut sub_types = vec![];
+        for (_, value) in &self.sub_types {
+            check_name(value, "sub_type", sub_type_names);
+            sub_types.push(value);
+        }
+        let type_check = if types.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                if !matches!(::nonebot_rs::prelude::EventTypeName::type_name(event).as_str(), #(#types)|*) {
+                    return false;
+                }
+            }
+        };
+        let sub_type_check = if sub_types.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                if !matches!(::nonebot_rs::prelude::EventTypeName::sub_type_name(event).as_deref(), Some(#(#sub_types)|*)) {
+                    return false;
+                }
+            }
+        };
+        quote! {
+            #type_check
+            #sub_type_check
+        }
+    }
+
+    /// 生成 Handler trait 中对应的方法
+    pub(crate) fn handler_tokens(&self) -> proc_macro2::TokenStream {
+        let cooldown = self.cooldown_tokens();
+        let meta = self.meta_tokens();
+        quote! {
+            #cooldown
+            #meta
+        }
+    }
+
+    fn meta_tokens(&self) -> proc_macro2::TokenStream {
+        if self.commands.is_empty() && !self.aliases.is_empty() {
+            abort!(
+                proc_macro2::Span::call_site(),
+                "alias 需要 bot_command 以固定命令开头"
+            );
+        }
+        let commands = self.commands.iter().chain(self.aliases.iter());
+        let description = option_tokens(&self.description);
+        let usage = option_tokens(&self.usage);
+        let examples = self.examples.iter();
+        let category = option_tokens(&self.category);
+        let visibility = self.visibility.as_deref().or(self.permission.as_deref());
+        let visibility = match visibility.unwrap_or("public") {
+            "admin" => quote! {::nonebot_rs::prelude::Visibility::Admin},
+            "superuser" => quote! {::nonebot_rs::prelude::Visibility::SuperUser},
+            "hidden" => quote! {::nonebot_rs::prelude::Visibility::Hidden},
+            _ => quote! {::nonebot_rs::prelude::Visibility::Public},
+        };
+        quote! {
+            fn meta(&self) -> ::nonebot_rs::prelude::MatcherMeta {
+                ::nonebot_rs::prelude::MatcherMeta {
+                    commands: vec![#(#commands.to_string()),*],
+                    description: #description,
+                    usage: #usage,
+                    examples: vec![#(#examples.to_string()),*],
+                    category: #category,
+                    visibility: #visibility,
+                }
+            }
+        }
+    }
+
+    fn cooldown_tokens(&self) -> proc_macro2::TokenStream {
+        let seconds = match self.cooldown {
+            None => {
+                if self.cooldown_scope.is_some() || self.cooldown_reply.is_some() {
+                    abort!(
+                        proc_macro2::Span::call_site(),
+                        "cooldown_scope/cooldown_reply 需要同时设置 cooldown"
+                    );
+                }
+                return quote! {};
+            }
+            Some(seconds) => seconds,
+        };
+        let scope = match self.cooldown_scope.as_deref().unwrap_or("user") {
+            "group" => quote! {::nonebot_rs::prelude::CooldownScope::Group},
+            "global" => quote! {::nonebot_rs::prelude::CooldownScope::Global},
+            _ => quote! {::nonebot_rs::prelude::CooldownScope::User},
+        };
+        let reply = match &self.cooldown_reply {
+            Some(reply) => quote! {.set_reply(#reply)},
+            None => quote! {},
+        };
+        quote! {
+            fn cooldown(&self) -> Option<::nonebot_rs::prelude::Cooldown> {
+                Some(::nonebot_rs::prelude::Cooldown::new(#scope, #seconds)#reply)
+            }
+        }
+    }
+}
+
+/// `NoticeType` 的 serde 名称
+const NOTICE_TYPES: &[&str] = &[
+    "group_upload",
+    "group_admin",
+    "group_decrease",
+    "group_increase",
+    "group_ban",
+    "friend_add",
+    "group_recall",
+    "friend_recall",
+    "group_card",
+    "offline_file",
+    "client_status",
+    "essence",
+    "notify",
+];
+
+/// `NoticeSubType` 的 serde 名称
+const NOTICE_SUB_TYPES: &[&str] = &[
+    "honor", "poke", "lucky_king", "title", "approve", "invite", "leave", "kick", "kick_me", "set",
+    "unset", "ban", "lift_ban", "add", "delete",
+];
+
+/// `RequestType` 的 serde 名称
+const REQUEST_TYPES: &[&str] = &["friend", "group"];
+
+/// `RequestSubType` 的 serde 名称
+const REQUEST_SUB_TYPES: &[&str] = &["add", "invite"];
+
+/// 检查事件类型名称，names 为 None 时不检查
+fn check_name(value: &syn::LitStr, key: &str, names: Option<&[&str]>) {
+    if let Some(names) = names {
+        if !names.contains(&value.value().as_str()) {
+            abort!(value.span(), "{} 只支持 {}", key, names.join("/"));
+        }
+    }
+}
+
+fn option_tokens(value: &Option<String>) -> proc_macro2::TokenStream {
+    match value {
+        Some(v) => quote! {Some(#v.to_string())},
+        None => quote! {None},
+    }
+}
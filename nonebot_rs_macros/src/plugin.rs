@@ -0,0 +1,219 @@
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::Lit::Str;
+use syn::Meta::NameValue;
+use syn::NestedMeta::Meta;
+use syn::{Attribute, AttributeArgs, Expr, Ident, Item, ItemMod, UseTree};
+
+use crate::utils::take_expr_list;
+
+/// #[plugin] 的参数
+#[derive(Default)]
+pub(crate) struct PluginAttrs {
+    name: Option<String>,
+    version: Option<String>,
+    description: Option<String>,
+    /// 配置类型
+    config: Option<syn::Type>,
+    /// `matchers = [...]` 中额外的 Matcher 表达式
+    matchers: Vec<Expr>,
+    /// `middlewares = [...]` 中的中间件表达式
+    middlewares: Vec<Expr>,
+    /// `jobs = [...]` 中额外的定时任务表达式
+    jobs: Vec<Expr>,
+}
+
+impl PluginAttrs {
+    /// 取出 `matchers = [...]`、`middlewares = [...]` 与 `jobs = [...]`，其不是合法的 attribute 参数，需在解析前移除
+    pub(crate) fn take_lists(&mut self, args: TokenStream) -> TokenStream {
+        let (args, matchers) = take_expr_list(args, "matchers", "Matcher");
+        let (args, middlewares) = take_expr_list(args, "middlewares", "Middleware");
+        let (args, jobs) = take_expr_list(args, "jobs", "ScheduledJob");
+        self.matchers = matchers;
+        self.middlewares = middlewares;
+        self.jobs = jobs;
+        args
+    }
+
+    fn parse(&mut self, attrs: AttributeArgs) {
+        for nm in attrs {
+            let nv = match &nm {
+                Meta(NameValue(nv)) if nv.path.segments.len() == 1 => nv,
+                _ => abort!(
+                    &nm.span(),
+                    "不支持的参数, 仅支持 name/version/description/config/matchers/middlewares/jobs"
+                ),
+            };
+            let ident = &nv.path.segments.first().unwrap().ident;
+            let value = match &nv.lit {
+                Str(value) => value,
+                _ => abort!(&ident.span(), "{}只支持字符串类型参数值", ident),
+            };
+            match ident.to_string().as_str() {
+                "name" => self.name = Some(value.value()),
+                "version" => self.version = Some(value.value()),
+                "description" => self.description = Some(value.value()),
+                "config" => match value.parse::<syn::Type>() {
+                    Ok(ty) => self.config = Some(ty),
+                    Err(_) => abort!(&value.span(), "config 需为配置类型"),
+                },
+                _ => abort!(&ident.span(), "不支持的参数名称"),
+            }
+        }
+    }
+}
+
+/// 属性的最后一段路径名，`#[nonebot_rs::prelude::event]` 为 event
+fn attr_name(attr: &Attribute) -> Option<String> {
+    attr.path.segments.last().map(|s| s.ident.to_string())
+}
+
+/// `#[send(..)]` 是否设置了 cron
+fn send_has_cron(attr: &Attribute) -> bool {
+    attr.tokens
+        .clone()
+        .into_iter()
+        .flat_map(|tt| match tt {
+            proc_macro2::TokenTree::Group(group) => group.stream().into_iter().collect(),
+            tt => vec![tt],
+        })
+        .any(|tt| matches!(tt, proc_macro2::TokenTree::Ident(ident) if ident == "cron"))
+}
+
+/// mod 上的文档注释
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(nv)) => match nv.lit {
+                Str(value) => Some(value.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n").trim().to_string())
+    }
+}
+
+/// use 语句是否引入了该名称
+fn use_declares(tree: &UseTree, ident: &Ident) -> bool {
+    match tree {
+        UseTree::Path(path) => use_declares(&path.tree, ident),
+        UseTree::Name(name) => name.ident == *ident,
+        UseTree::Rename(rename) => rename.rename == *ident,
+        UseTree::Group(group) => group.items.iter().any(|tree| use_declares(tree, ident)),
+        UseTree::Glob(_) => false,
+    }
+}
+
+/// mod 中是否声明或引入了该类型名
+fn declares(items: &[Item], ident: &Ident) -> bool {
+    items.iter().any(|item| match item {
+        Item::Struct(item) => item.ident == *ident,
+        Item::Enum(item) => item.ident == *ident,
+        Item::Union(item) => item.ident == *ident,
+        Item::Type(item) => item.ident == *ident,
+        Item::Use(item) => use_declares(&item.tree, ident),
+        _ => false,
+    })
+}
+
+/// 配置类型在 mod 内解析，单段类型名未在 mod 中声明时从 mod 外（`super::`）解析
+fn config_type(config: &syn::Type, items: &[Item]) -> TokenStream {
+    if let syn::Type::Path(ty) = config {
+        if ty.qself.is_none()
+            && ty.path.leading_colon.is_none()
+            && ty.path.segments.len() == 1
+            && !declares(items, &ty.path.segments[0].ident)
+        {
+            return quote! {super::#config};
+        }
+    }
+    quote! {#config}
+}
+
+pub(crate) fn expand(mut plugin_attrs: PluginAttrs, attrs: AttributeArgs, mut module: ItemMod) -> TokenStream {
+    plugin_attrs.parse(attrs);
+    let items = match &mut module.content {
+        Some((_, items)) => items,
+        None => abort!(&module.span(), "#[plugin] 只支持内联 mod，需写为 mod name {{ .. }}"),
+    };
+
+    // 收集 mod 中的 #[event]、#[scheduler] 与设置了 cron 的 #[send]
+    let mut matchers = vec![];
+    let mut jobs = vec![];
+    for item in items.iter() {
+        if let Item::Fn(method) = item {
+            let ident = &method.sig.ident;
+            for attr in &method.attrs {
+                match attr_name(attr).as_deref() {
+                    Some("event") => matchers.push(quote! {#ident::matcher()}),
+                    Some("scheduler") => jobs.push(quote! {#ident}),
+                    Some("send") if send_has_cron(attr) => jobs.push(quote! {#ident}),
+                    _ => {}
+                }
+            }
+        }
+    }
+    matchers.extend(plugin_attrs.matchers.iter().map(|expr| quote! {#expr}));
+    jobs.extend(plugin_attrs.jobs.iter().map(|expr| quote! {#expr}));
+
+    let name = plugin_attrs
+        .name
+        .clone()
+        .unwrap_or_else(|| module.ident.to_string());
+    let version = match &plugin_attrs.version {
+        Some(version) => quote! {#version},
+        None => quote! {env!("CARGO_PKG_VERSION")},
+    };
+    let description = plugin_attrs
+        .description
+        .clone()
+        .or_else(|| doc_comment(&module.attrs))
+        .unwrap_or_default();
+
+    let mut def = quote! {
+        ::nonebot_rs::prelude::PluginDef::new(#name, #version, #description, module_path!())
+    };
+    if !matchers.is_empty() {
+        def.extend(quote! {
+            .matchers(|matchers| {
+                #(matchers.add(#matchers);)*
+            })
+        });
+    }
+    if !plugin_attrs.middlewares.is_empty() {
+        let middlewares = &plugin_attrs.middlewares;
+        def.extend(quote! {
+            .middlewares(|middlewares| {
+                #(middlewares.add(#middlewares);)*
+            })
+        });
+    }
+    if !jobs.is_empty() {
+        def.extend(quote! {
+            .jobs(|| vec![#(Box::new(#jobs) as ::nonebot_rs::prelude::BoxedJob),*])
+        });
+    }
+    if let Some(config) = &plugin_attrs.config {
+        let config = config_type(config, items);
+        def.extend(quote! {
+            .config(::nonebot_rs::prelude::check_plugin_config::<#config>)
+        });
+    }
+
+    let submit: Item = syn::parse_quote! {
+        ::nonebot_rs::inventory::submit! {
+            #def
+        }
+    };
+    items.push(submit);
+    quote! {#module}
+}
@@ -0,0 +1,205 @@
+use proc_macro2::{Span, TokenStream};
+use proc_macro_error::abort;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::Lit::{Int, Str};
+use syn::Meta::NameValue;
+use syn::NestedMeta::Meta;
+use syn::{AttributeArgs, LitStr};
+use regex::Regex;
+use std::str::FromStr;
+
+/// #[scheduler] 的调度设置项
+#[derive(Default, Debug)]
+pub(crate) struct SchedulerAttrs {
+    pub(crate) cron: Option<String>,
+    pub(crate) tz: Option<String>,
+    /// 执行间隔
+    pub(crate) every: Option<LitStr>,
+    /// RFC 3339 时刻
+    pub(crate) at: Option<String>,
+    /// 启动后延迟
+    pub(crate) delay: Option<LitStr>,
+    /// 随机延迟上限
+    pub(crate) jitter: Option<LitStr>,
+    pub(crate) max_runs: Option<u64>,
+    /// 执行任务的 Bot，`JobTarget` 表达式
+    pub(crate) target: Option<TokenStream>,
+}
+
+/// 编译期检查 cron 表达式
+pub(crate) fn check_cron(value: &LitStr) {
+    if let Err(e) = cron::Schedule::from_str(&value.value()) {
+        abort!(&value.span(), "cron 表达式无效: {}", e);
+    }
+}
+
+/// 编译期检查时区，支持 `+08:00`、`+0800`、`-05`、`UTC` 与 IANA 时区名
+fn check_tz(value: &LitStr) {
+    let tz = value.value();
+    let offset = Regex::new(r"^[+-]([01]\d|2[0-3])(:?[0-5]\d)?$").unwrap();
+    if tz.eq_ignore_ascii_case("utc")
+        || tz == "Z"
+        || offset.is_match(&tz)
+        || tz.parse::<chrono_tz::Tz>().is_ok()
+    {
+        return;
+    }
+    abort!(&value.span(), "时区无效，应形如 +08:00、UTC 或 Asia/Shanghai");
+}
+
+/// 以 `parse_duration` 在编译期检查时长，`every` 还需大于 0
+fn duration_check(name: &str, value: &LitStr) -> TokenStream {
+    let (pattern, msg) = if name == "every" {
+        (quote! {Some(d) if d.as_secs() > 0}, "every 需为大于 0 的时长，如 30s、10m 或 1h30m")
+    } else {
+        (quote! {Some(_)}, "时长无效，应形如 30s、10m 或 1h30m")
+    };
+    quote_spanned! {value.span()=>
+        const _: () = match ::nonebot_rs::prelude::parse_duration(#value) {
+            #pattern => (),
+            _ => panic!(#msg),
+        };
+    }
+}
+
+/// 编译期检查通过的时长，运行时解析
+fn duration_tokens(value: &LitStr) -> TokenStream {
+    quote! {
+        ::nonebot_rs::prelude::parse_duration(#value).expect("时长已在编译期检查")
+    }
+}
+
+impl SchedulerAttrs {
+    pub(crate) fn parse(attrs: AttributeArgs, span: Span) -> Self {
+        let mut scheduler_attrs = SchedulerAttrs::default();
+        for nm in attrs {
+            let nv = match &nm {
+                Meta(NameValue(nv)) if nv.path.segments.len() == 1 => nv,
+                _ => abort!(&nm.span(), "表达式有且只能有一个片段"),
+            };
+            let ident = &nv.path.segments.first().unwrap().ident;
+            let name = ident.to_string();
+            match (name.as_str(), &nv.lit) {
+                ("max_runs", Int(value)) => match value.base10_parse() {
+                    Ok(max_runs) => scheduler_attrs.max_runs = Some(max_runs),
+                    Err(_) => abort!(&value.span(), "max_runs 需为正整数"),
+                },
+                ("max_runs", _) => abort!(&ident.span(), "max_runs只支持整数类型参数值"),
+                ("bot", Int(value)) => match value.base10_parse::<i64>() {
+                    Ok(bot_id) => {
+                        scheduler_attrs.target =
+                            Some(quote! {::nonebot_rs::prelude::JobTarget::Bot(#bot_id)})
+                    }
+                    Err(_) => abort!(&value.span(), "bot 需为 bot_id"),
+                },
+                ("bot", Str(value)) => match value.value().as_str() {
+                    "each" => {
+                        scheduler_attrs.target =
+                            Some(quote! {::nonebot_rs::prelude::JobTarget::EachBot})
+                    }
+                    "any" => {
+                        scheduler_attrs.target =
+                            Some(quote! {::nonebot_rs::prelude::JobTarget::AnyBot})
+                    }
+                    _ => abort!(&value.span(), "bot 只支持 \"each\"、\"any\" 或 bot_id"),
+                },
+                ("bot", _) => abort!(&ident.span(), "bot 只支持 \"each\"、\"any\" 或 bot_id"),
+                ("every", Str(value)) => scheduler_attrs.every = Some(value.clone()),
+                ("delay", Str(value)) => scheduler_attrs.delay = Some(value.clone()),
+                ("jitter", Str(value)) => scheduler_attrs.jitter = Some(value.clone()),
+                ("cron", Str(value)) => {
+                    check_cron(value);
+                    scheduler_attrs.cron = Some(value.value())
+                }
+                ("tz", Str(value)) => {
+                    check_tz(value);
+                    scheduler_attrs.tz = Some(value.value())
+                }
+                ("at", Str(value)) => {
+                    if let Err(e) = chrono::DateTime::parse_from_rfc3339(&value.value()) {
+                        abort!(&value.span(), "at 需为 RFC 3339 时刻，如 2022-01-01T08:00:00+08:00: {}", e);
+                    }
+                    scheduler_attrs.at = Some(value.value())
+                }
+                ("cron" | "tz" | "at" | "every" | "delay" | "jitter", _) => {
+                    abort!(&ident.span(), "{}只支持字符串类型参数值", ident)
+                }
+                _ => abort!(&ident.span(), "不支持的参数名称"),
+            }
+        }
+        let schedules = [
+            scheduler_attrs.cron.is_some(),
+            scheduler_attrs.every.is_some(),
+            scheduler_attrs.at.is_some(),
+            scheduler_attrs.delay.is_some(),
+        ];
+        match schedules.iter().filter(|set| **set).count() {
+            0 => abort!(span, "需要 cron、every、at 或 delay 其中之一"),
+            1 => {}
+            _ => abort!(span, "cron、every、at 与 delay 只能设置其中之一"),
+        }
+        if scheduler_attrs.tz.is_some() && scheduler_attrs.cron.is_none() {
+            abort!(span, "tz 只能与 cron 一同使用");
+        }
+        if scheduler_attrs.max_runs == Some(0) {
+            abort!(span, "max_runs 需为正整数");
+        }
+        scheduler_attrs
+    }
+
+    /// 生成时长的编译期检查，置于 impl 之外
+    pub(crate) fn checks(&self) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        for (name, value) in [("every", &self.every), ("delay", &self.delay), ("jitter", &self.jitter)] {
+            if let Some(value) = value {
+                tokens.extend(duration_check(name, value));
+            }
+        }
+        tokens
+    }
+
+    /// 生成 `ScheduledJob` 中 cron 之外的调度方法
+    pub(crate) fn tokens(&self) -> TokenStream {
+        let schedule = if let Some(cron) = &self.cron {
+            let tz = self.tz.as_deref().unwrap_or("UTC");
+            quote! {::nonebot_rs::prelude::Schedule::cron_tz(#cron, #tz)}
+        } else if let Some(every) = &self.every {
+            let every = duration_tokens(every);
+            quote! {::nonebot_rs::prelude::Schedule::every(#every)}
+        } else if let Some(at) = &self.at {
+            quote! {::nonebot_rs::prelude::Schedule::at_str(#at)}
+        } else {
+            let delay = self.delay.as_ref().map(duration_tokens);
+            quote! {Ok(::nonebot_rs::prelude::Schedule::after(#delay))}
+        };
+        let mut tokens = quote! {
+            fn schedule(&self) -> Result<::nonebot_rs::prelude::Schedule, ::nonebot_rs::prelude::ScheduleError> {
+                #schedule
+            }
+        };
+        if let Some(jitter) = &self.jitter {
+            let jitter = duration_tokens(jitter);
+            tokens.extend(quote! {
+                fn jitter(&self) -> Option<::std::time::Duration> {
+                    Some(#jitter)
+                }
+            });
+        }
+        if let Some(max_runs) = self.max_runs {
+            tokens.extend(quote! {
+                fn max_runs(&self) -> Option<u64> {
+                    Some(#max_runs)
+                }
+            });
+        }
+        if let Some(target) = &self.target {
+            tokens.extend(quote! {
+                fn target(&self) -> ::nonebot_rs::prelude::JobTarget {
+                    #target
+                }
+            });
+        }
+        tokens
+    }
+}
@@ -0,0 +1,195 @@
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::Lit::{Int, Str};
+use syn::Meta::NameValue;
+use syn::NestedMeta::Meta;
+use syn::{AttributeArgs, FnArg, ItemFn, Pat, ReturnType};
+
+/// #[send] 的参数
+#[derive(Default)]
+struct SendAttrs {
+    /// 发送目标
+    target: Option<TokenStream>,
+    template: Option<String>,
+    cron: Option<String>,
+}
+
+fn parse_attrs(method: &ItemFn, attrs: AttributeArgs) -> SendAttrs {
+    let mut send_attrs = SendAttrs::default();
+    for nm in attrs {
+        let nv = match &nm {
+            Meta(NameValue(nv)) if nv.path.segments.len() == 1 => nv,
+            _ => abort!(&nm.span(), "不支持的参数, 仅支持 group/user/template/cron"),
+        };
+        let ident = &nv.path.segments.first().unwrap().ident;
+        let ident_name = ident.to_string();
+        match ident_name.as_str() {
+            "group" | "user" => {
+                let id = match &nv.lit {
+                    Int(value) => match value.base10_parse::<i64>() {
+                        Ok(id) => id,
+                        Err(_) => abort!(&nv.lit.span(), "{}必须是整数", ident_name),
+                    },
+                    _ => abort!(&ident.span(), "{}只支持整数类型参数值", ident_name),
+                };
+                if send_attrs.target.is_some() {
+                    abort!(&ident.span(), "group 与 user 只能设置一个");
+                }
+                send_attrs.target = Some(if ident_name == "group" {
+                    quote! {::nonebot_rs::prelude::SendTarget::Group(#id)}
+                } else {
+                    quote! {::nonebot_rs::prelude::SendTarget::Private(#id)}
+                });
+            }
+            "template" => match &nv.lit {
+                Str(value) => send_attrs.template = Some(value.value()),
+                _ => abort!(&ident.span(), "template只支持字符串类型参数值"),
+            },
+            "cron" => match &nv.lit {
+                Str(value) => {
+                    crate::scheduler_attr::check_cron(value);
+                    send_attrs.cron = Some(value.value())
+                }
+                _ => abort!(&ident.span(), "cron只支持字符串类型参数值"),
+            },
+            _ => abort!(&ident.span(), "不支持的参数名称"),
+        }
+    }
+    if send_attrs.target.is_none() {
+        abort!(&method.sig.ident.span(), "需要设置发送目标 group 或 user");
+    }
+    send_attrs
+}
+
+// 模板中的 {name} 占位符，不含 {{ }} 转义
+fn template_names(template: &str) -> Vec<String> {
+    let placeholder = regex::Regex::new(r"\{([^{}:]*)(:[^{}]*)?\}").expect("template正则表达式编译失败");
+    let template = template.replace("{{", "").replace("}}", "");
+    placeholder
+        .captures_iter(&template)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+pub(crate) fn expand(attrs: AttributeArgs, method: ItemFn) -> TokenStream {
+    let send_attrs = parse_attrs(&method, attrs);
+    if method.sig.asyncness.is_none() {
+        abort!(&method.sig.span(), "必须是async方法");
+    }
+
+    // 参数均需为标识符，作为模板变量或传给方法体
+    let mut names = vec![];
+    for param in &method.sig.inputs {
+        match param {
+            FnArg::Receiver(_) => abort!(&param.span(), "不支持self"),
+            FnArg::Typed(pt) => match pt.pat.as_ref() {
+                Pat::Ident(pi) if pi.ident == "bot" || pi.ident == "bots" => {
+                    abort!(&pi.ident.span(), "参数名 {} 与生成的方法参数冲突", pi.ident)
+                }
+                Pat::Ident(pi) => names.push(&pi.ident),
+                _ => abort!(&pt.pat.span(), "参数必须是标识符"),
+            },
+        }
+    }
+    if send_attrs.cron.is_some() && !names.is_empty() {
+        abort!(&method.sig.inputs.span(), "设置 cron 的方法不能有参数");
+    }
+
+    let ident = &method.sig.ident;
+    let vis = &method.vis;
+    let inputs = &method.sig.inputs;
+    let target = send_attrs.target.as_ref().unwrap();
+
+    let render = match &send_attrs.template {
+        Some(template) => {
+            if !method.block.stmts.is_empty() {
+                abort!(&method.block.span(), "使用 template 时方法体必须为空");
+            }
+            let placeholders = template_names(template);
+            for placeholder in &placeholders {
+                if !names.iter().any(|name| name.to_string().eq(placeholder)) {
+                    abort!(&method.sig.ident.span(), "template 中的 {{{}}} 没有对应的参数", placeholder);
+                }
+            }
+            for name in &names {
+                if !placeholders.contains(&name.to_string()) {
+                    abort!(&name.span(), "参数 {} 未在 template 中使用", name);
+                }
+            }
+            quote! {
+                vec![::nonebot_rs::prelude::Message::text(format!(#template, #(#names = #names),*))]
+            }
+        }
+        None => {
+            let output = match &method.sig.output {
+                ReturnType::Default => abort!(
+                    &method.sig.span(),
+                    "未设置 template 时方法需返回消息 (String/Message/MessageChain)"
+                ),
+                output => output,
+            };
+            let block = &method.block;
+            quote! {
+                async fn build(#inputs) #output #block
+                ::nonebot_rs::prelude::IntoMessageChain::into_message_chain(build(#(#names),*).await)
+            }
+        }
+    };
+
+    let job = match &send_attrs.cron {
+        Some(cron) => {
+            let name = ident.to_string();
+            quote! {
+                impl ::nonebot_rs::prelude::ScheduledJob for #ident {
+                    fn name(&self) -> String {
+                        #name.to_string()
+                    }
+                    fn cron(&self) -> String {
+                        #cron.to_string()
+                    }
+                    fn target(&self) -> ::nonebot_rs::prelude::JobTarget {
+                        ::nonebot_rs::prelude::JobTarget::AnyBot
+                    }
+                    fn call(&self, bot: std::sync::Arc<::nonebot_rs::prelude::Bot>) -> std::pin::Pin<Box<dyn std::future::Future<Output=()> + Send + 'static>> {
+                        Box::pin(async move {
+                            if let Err(err) = Self::send(&bot).await {
+                                ::nonebot_rs::prelude::log_event!(::nonebot_rs::prelude::Level::ERROR, "Send {} failed: {}", #name, err);
+                            }
+                        })
+                    }
+                }
+            }
+        }
+        None => quote! {},
+    };
+
+    quote! {
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Clone, Copy, Default)]
+        #vis struct #ident;
+
+        impl #ident {
+            /// 发送目标
+            pub const TARGET: ::nonebot_rs::prelude::SendTarget = #target;
+
+            /// 渲染消息
+            pub async fn render(#inputs) -> ::nonebot_rs::prelude::MessageChain {
+                #render
+            }
+
+            /// 通过指定 Bot 发送
+            pub async fn send(bot: &::nonebot_rs::prelude::Bot, #inputs) -> ::nonebot_rs::NBResult<()> {
+                Self::TARGET.send_by(bot, Self::render(#(#names),*).await).await
+            }
+
+            /// 通过任一已连接的 Bot 发送
+            pub async fn send_any(bots: &::nonebot_rs::BotGetter, #inputs) -> ::nonebot_rs::NBResult<()> {
+                Self::TARGET.send_any(bots, Self::render(#(#names),*).await).await
+            }
+        }
+
+        #job
+    }
+}
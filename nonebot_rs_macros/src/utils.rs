@@ -1,8 +1,9 @@
-use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, Ident, TokenStream, TokenTree};
 use proc_macro_error::abort;
 use quote::quote;
-use regex::internal::Input;
-use syn::{FnArg, Pat, Type};
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+use syn::{Expr, FnArg, GenericArgument, Pat, PathArguments, Token, Type};
 use syn::spanned::Spanned;
 
 pub fn event_param_match(event_param: &FnArg) -> Option<(&Pat, &Type)> {
@@ -51,76 +52,130 @@ pub fn matcher_param_match(matcher_param: &FnArg) -> Option<(&Pat, &Type)> {
     Some((matcher_param_pat, matcher_param_ty))
 }
 
-pub fn state_match(state_param: &FnArg) -> Option<(&Pat, &Type)> {
-    let state_param = match state_param {
-        FnArg::Receiver(_) => abort!(&state_param.span(), "不支持self"),
-        FnArg::Typed(pt) => pt,
+/// handler 参数的来源
+pub enum HandlerParam<'a> {
+    /// 事件
+    Event,
+    /// 匹配器
+    Matcher,
+    /// bot_command 中的参数
+    Command(&'a Ident),
+    /// 通过 FromEvent 提取
+    Extract,
+}
+
+/// 获取 `&mut Matcher<E>` 中的事件类型
+fn matcher_event_ty(matcher_ty: &Type) -> Option<&Type> {
+    let path = match matcher_ty {
+        Type::Reference(reference) => match reference.elem.as_ref() {
+            Type::Path(path) => path,
+            _ => return None,
+        },
+        _ => return None,
     };
-    let state_param_pat = state_param.pat.as_ref();
-    let state_param_ty = state_param.ty.as_ref();
-    
-    let state_param_ty_ = quote! {#state_param_ty};
-    match state_param_ty_.to_string().as_str() {
-        "& mut State" => {
-            Some((state_param_pat, state_param_ty))
-        }
-        _ => {
-            return None;
-        }
+    match &path.path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first()? {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
-pub fn params_command<'a>(params: &'a Vec<&'a FnArg>) -> (Option<(&'a Pat, &'a Type)>, (&'a Pat, &'a Type), (&'a Pat, &'a Type), usize) {
-    let first_fn_1 = params.first().expect("参数过少");
-    if params.len() < 2 {
-        abort!(first_fn_1.span(),
-            format!("参数过少,必须有状态(可选),事件,匹配器作为参数"),
-        )
+fn param_ident(param: &FnArg) -> Option<&Ident> {
+    match param {
+        FnArg::Typed(pt) => match pt.pat.as_ref() {
+            Pat::Ident(pi) => Some(&pi.ident),
+            _ => None,
+        },
+        FnArg::Receiver(_) => None,
     }
-    match state_match(first_fn_1) {
-        None => {
-            match event_param_match(first_fn_1) {
-                None => {
-                    abort!(first_fn_1.span(),
-                        format!("未知的参数类型, 事件必须作为第一个参数, "),
-                    );
-                }
-                Some((event_pat, event_ty)) => {
-                    let (matcher_pat, matcher_ty) = match matcher_param_match(params.get(1).expect("第二个参数获取失败(1)")) {
-                        None => {
-                            abort!(first_fn_1.span(),
-                            format!("未知的参数类型, 匹配器必须作为第二个参数, "),
-                            );
-                        }
-                        Some((pat, ty)) => {
-                            (pat, ty)
-                        }
-                    };
-                    (None, (event_pat, event_ty), (matcher_pat, matcher_ty), 2)
+}
+
+/// 对 handler 参数按来源分类，参数顺序不限
+///
+/// 事件与匹配器至少需要一个以确定事件类型，名称出现在 bot_command 中的参数为命令参数，
+/// 其余参数通过 FromEvent 提取
+pub fn classify_params<'a>(
+    params: &'a [&'a FnArg],
+    command_names: &[String],
+) -> (&'a Type, Vec<(HandlerParam<'a>, &'a Pat, &'a Type)>) {
+    let mut event_ty = None;
+    let mut matcher_ty = None;
+    let mut classified = vec![];
+    for param in params {
+        if let Some((pat, ty)) = event_param_match(param) {
+            if event_ty.replace(ty).is_some() {
+                abort!(param.span(), "事件参数只能有一个");
+            }
+            classified.push((HandlerParam::Event, pat, ty));
+        } else if let Some((pat, ty)) = matcher_param_match(param) {
+            if matcher_ty.replace(ty).is_some() {
+                abort!(param.span(), "匹配器参数只能有一个");
+            }
+            classified.push((HandlerParam::Matcher, pat, ty));
+        } else {
+            let pt = match param {
+                FnArg::Receiver(_) => abort!(param.span(), "不支持self"),
+                FnArg::Typed(pt) => pt,
+            };
+            match param_ident(param) {
+                Some(ident) if command_names.contains(&ident.to_string()) => {
+                    classified.push((HandlerParam::Command(ident), pt.pat.as_ref(), pt.ty.as_ref()))
                 }
+                _ => classified.push((HandlerParam::Extract, pt.pat.as_ref(), pt.ty.as_ref())),
+            }
+        }
+    }
+    if let (Some(event_ty), Some(matcher_ty)) = (event_ty, matcher_ty) {
+        if let Some(ty) = matcher_event_ty(matcher_ty) {
+            if quote! {#ty}.to_string() != quote! {#event_ty}.to_string() {
+                abort!(matcher_ty.span(), "匹配器的事件类型必须与事件参数一致");
             }
         }
-        Some((state_pat, state_ty)) => {
-            match event_param_match(params.get(1).expect("第二个参数获取失败(2)")) {
-                None => {
-                    abort!(first_fn_1.span(),
-                    format!("未知的参数类型,事件必须作为第二个参数, "),
-                    );
+    }
+    let event_ty = match (event_ty, matcher_ty) {
+        (Some(event_ty), _) => event_ty,
+        (None, Some(matcher_ty)) => match matcher_event_ty(matcher_ty) {
+            Some(ty) => ty,
+            None => abort!(matcher_ty.span(), "无法从匹配器参数获取事件类型"),
+        },
+        (None, None) => abort!(
+            params.first().map(|p| p.span()).unwrap_or_else(proc_macro2::Span::call_site),
+            "需要事件或匹配器作为参数"
+        ),
+    };
+    (event_ty, classified)
+}
+
+/// 取出 `key = [...]` 表达式数组，其不是合法的 attribute 参数，需在解析前移除
+pub fn take_expr_list(args: TokenStream, key: &str, what: &str) -> (TokenStream, Vec<Expr>) {
+    let tokens: Vec<TokenTree> = args.into_iter().collect();
+    let mut rest = vec![];
+    let mut exprs = vec![];
+    let mut i = 0;
+    while i < tokens.len() {
+        if let (TokenTree::Ident(ident), Some(TokenTree::Punct(eq)), Some(TokenTree::Group(group))) =
+            (&tokens[i], tokens.get(i + 1), tokens.get(i + 2))
+        {
+            if ident == key && eq.as_char() == '=' && group.delimiter() == Delimiter::Bracket {
+                let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+                match parser.parse2(group.stream()) {
+                    Ok(list) => exprs.extend(list),
+                    Err(err) => abort!(group.span(), "{} 必须是 {} 表达式数组: {}", key, what, err),
                 }
-                Some((event_pat, event_ty)) => {
-                    let (matcher_pat, matcher_ty) = match matcher_param_match(params.get(2).expect("第三个参数获取失败(1)")) {
-                        None => {
-                            abort!(first_fn_1.span(),
-                            format!("未知的参数类型, 匹配器必须作为第三个参数, "),
-                            );
-                        }
-                        Some((pat, ty)) => {
-                            (pat, ty)
-                        }
-                    };
-                    (Some((state_pat, state_ty)), (event_pat, event_ty), (matcher_pat, matcher_ty), 3)
+                i += 3;
+                // 跳过其后的逗号
+                if let Some(TokenTree::Punct(comma)) = tokens.get(i) {
+                    if comma.as_char() == ',' {
+                        i += 1;
+                    }
                 }
+                continue;
             }
         }
+        rest.push(tokens[i].clone());
+        i += 1;
     }
-}
\ No newline at end of file
+    (rest.into_iter().collect(), exprs)
+}
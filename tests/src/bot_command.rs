@@ -0,0 +1,63 @@
+use nonebot_rs::prelude::{
+    event, ErrorHook, Handler, HandlerFailure, Matcher, Message, MessageEvent,
+    PrivateMessageEvent, PrivateSender, PrivateSubType,
+};
+use std::sync::{Arc, Mutex};
+
+#[event(bot_command = "/add {a} {b}")]
+async fn add(matcher: &mut Matcher<MessageEvent>, a: i32, b: i32) -> nonebot_rs::NBResult<()> {
+    matcher.send_text(&(a + b).to_string()).await;
+    Ok(())
+}
+
+fn message_event(text: &str) -> MessageEvent {
+    MessageEvent::Private(PrivateMessageEvent {
+        time: 1631193409,
+        self_id: 11,
+        sub_type: PrivateSubType::Friend,
+        message_id: 1,
+        user_id: 22,
+        message: vec![Message::text(text)],
+        raw_message: text.to_string(),
+        font: 0,
+        sender: PrivateSender {
+            user_id: 22,
+            nickname: String::new(),
+            sex: String::new(),
+            age: 0,
+        },
+    })
+}
+
+#[tokio::test]
+async fn usage_test() {
+    let replies = Arc::new(Mutex::new(vec![]));
+    let error_hook = ErrorHook::default();
+    error_hook.set_callback({
+        let replies = replies.clone();
+        move |context| {
+            if let HandlerFailure::Error(err) = &context.failure {
+                replies.lock().unwrap().push(err.to_string());
+            }
+        }
+    });
+    let mut matcher = Matcher::new("add", add::default());
+    matcher.set_error_hook(error_hook);
+
+    // 命令名匹配但参数缺失或无法解析时视为匹配，回复用法
+    for (text, error) in [
+        ("/add 1 x", "参数 b 的值 x 无效"),
+        ("/add 1", "缺少参数 <b>"),
+        ("/add", "缺少参数 <a>"),
+    ] {
+        let mut event = message_event(text);
+        assert!(add::default().match_(&mut event));
+        add::default().handle(event, &mut matcher).await;
+        let reply = replies.lock().unwrap().pop().unwrap();
+        assert_eq!(reply, format!("{}\n用法: /add <a> <b>", error));
+    }
+
+    assert!(add::default().match_(&mut message_event("/add 1 2")));
+    assert!(!add::default().match_(&mut message_event("/sub 1 2")));
+    assert!(replies.lock().unwrap().is_empty());
+}
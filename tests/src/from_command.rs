@@ -0,0 +1,103 @@
+use nonebot_rs::prelude::{CommandErrorKind, FromCommand};
+
+#[derive(Debug, PartialEq, FromCommand)]
+enum Level {
+    Low,
+    HighPriority,
+    #[command(name = "max", alias = "top")]
+    Maximum,
+}
+
+fn positive(value: &u32) -> Result<(), &'static str> {
+    if *value == 0 {
+        return Err("必须大于 0");
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, FromCommand)]
+struct Remind {
+    minutes: u32,
+    #[command(name = "who")]
+    target: Option<String>,
+    #[command(short = 'r', default = "1", validate = "positive")]
+    repeat: u32,
+    #[command(long)]
+    level: Option<Level>,
+    #[command(short = 'q')]
+    quiet: bool,
+    words: Vec<String>,
+}
+
+#[test]
+fn struct_test() {
+    assert_eq!(
+        Remind::usage(),
+        "<minutes> [who] [words...] [-r|--repeat <repeat>] [--level <level>] [-q|--quiet]"
+    );
+    let remind = Remind::parse_command("10 alice drink water -qr 3 --level high_priority").unwrap();
+    assert_eq!(
+        remind,
+        Remind {
+            minutes: 10,
+            target: Some("alice".to_string()),
+            repeat: 3,
+            level: Some(Level::HighPriority),
+            quiet: true,
+            words: vec!["drink".to_string(), "water".to_string()],
+        }
+    );
+    // 缺省值与可选参数
+    let remind = Remind::parse_command("5").unwrap();
+    assert_eq!(
+        (remind.target, remind.repeat, remind.quiet),
+        (None, 1, false)
+    );
+    assert!(remind.words.is_empty());
+}
+
+#[test]
+fn struct_error_test() {
+    let kind = |text: &str| Remind::parse_command(text).unwrap_err().kind;
+    assert_eq!(
+        kind(""),
+        CommandErrorKind::MissingArgument("minutes".to_string())
+    );
+    assert!(
+        matches!(kind("ten"), CommandErrorKind::InvalidValue { name, value } if name == "minutes" && value == "ten")
+    );
+    assert_eq!(
+        kind("5 --repeat 0"),
+        CommandErrorKind::Rejected {
+            name: "--repeat".to_string(),
+            reason: "必须大于 0".to_string(),
+        }
+    );
+    assert_eq!(
+        kind("5 --unknown"),
+        CommandErrorKind::UnknownOption("--unknown".to_string())
+    );
+    let err = Remind::parse_command("5 --level urgent").unwrap_err();
+    assert_eq!(
+        err.kind,
+        CommandErrorKind::InvalidChoice {
+            name: "level".to_string(),
+            value: "urgent".to_string(),
+            choices: vec![
+                "low".to_string(),
+                "high_priority".to_string(),
+                "max".to_string()
+            ],
+        }
+    );
+    assert_eq!(err.usage, Remind::usage());
+}
+
+#[test]
+fn enum_test() {
+    assert_eq!(Level::CHOICES, &["low", "high_priority", "max"]);
+    assert_eq!(Level::usage(), "<low|high_priority|max>");
+    assert_eq!("HIGH_PRIORITY".parse(), Ok(Level::HighPriority));
+    assert_eq!("top".parse(), Ok(Level::Maximum));
+    assert!("highpriority".parse::<Level>().is_err());
+}
@@ -10,3 +10,9 @@ mod tests {
         };
     }
 }
+
+#[cfg(test)]
+mod from_command;
+
+#[cfg(test)]
+mod bot_command;